@@ -0,0 +1,762 @@
+use super::mixed_volume;
+use crate::interact_mod::component::{Layer, PlaylistState, SoundItem};
+use rodio::cpal::traits::HostTrait;
+use rodio::source::{SeekError, UniformSourceIterator};
+use rodio::{Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Samples of every source we hand to the sink
+pub type BoxedSource = Box<dyn Source<Item = f32> + Send>;
+
+/// Output device picked on the command line, the default output when unset
+static OUTPUT_DEVICE: OnceLock<String> = OnceLock::new();
+
+/// Names of the audio output devices of the default host
+pub fn output_devices() -> Vec<String> {
+    rodio::cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Every sound and the playlist play on this device from now on
+pub fn set_output_device(name: &str) {
+    let _ = OUTPUT_DEVICE.set(name.to_owned());
+}
+
+/// Stream on the picked output device, or on the default one
+pub fn open_output() -> Result<(OutputStream, OutputStreamHandle), String> {
+    let Some(name) = OUTPUT_DEVICE.get() else {
+        return OutputStream::try_default().map_err(|e| e.to_string());
+    };
+    let device = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| e.to_string())?
+        .find(|d| d.name().is_ok_and(|n| &n == name))
+        .ok_or_else(|| format!("Audio output {} not found", name))?;
+    OutputStream::try_from_device(&device).map_err(|e| e.to_string())
+}
+
+/// Open the sound item from the sound directory, mixing the layers of a layered cue
+pub fn open_item(dir: &str, item: &SoundItem) -> Result<BoxedSource, String> {
+    if item.layers.is_empty() {
+        return open_file(dir, &item.name);
+    }
+    Ok(Box::new(Layered::new(dir, &item.layers)?))
+}
+
+/// Open the sound item with its fades, the chain shared by the playback and the bounce
+pub fn playback_source(
+    dir: &str,
+    item: &SoundItem,
+    fade_in: Option<Duration>,
+    fade_out: Option<Duration>,
+) -> Result<BoxedSource, String> {
+    let source = open_item(dir, item)?;
+    Ok(match (fade_in, fade_out) {
+        (Some(fade_in), Some(fade_out)) => Box::new(source.fade_in(fade_in).fade_out(fade_out)),
+        (Some(fade_in), None) => Box::new(source.fade_in(fade_in)),
+        (None, Some(fade_out)) => Box::new(source.fade_out(fade_out)),
+        _ => source,
+    })
+}
+
+/// First and last position where the sound goes over the threshold in dBFS, None if it never does
+pub fn detect_sound(
+    dir: &str,
+    item: &SoundItem,
+    threshold_db: f32,
+) -> Result<Option<(Duration, Duration)>, String> {
+    let source = open_item(dir, item)?;
+    let channels = source.channels().max(1) as usize;
+    let sample_rate = source.sample_rate().max(1) as f64;
+    let threshold = 10f32.powf(threshold_db / 20.0);
+    let mut first = None;
+    let mut last = 0;
+    for (i, sample) in source.enumerate() {
+        if sample.abs() > threshold {
+            let frame = i / channels;
+            first.get_or_insert(frame);
+            last = frame + 1;
+        }
+    }
+    let position = |frame: usize| Duration::from_secs_f64(frame as f64 / sample_rate);
+    Ok(first.map(|first| (position(first), position(last))))
+}
+
+fn open_file(dir: &str, name: &str) -> Result<BoxedSource, String> {
+    let mut sound_path = PathBuf::from(dir);
+    sound_path.push(name);
+    let file = File::open(&sound_path)
+        .map_err(|e| format!("Cannot open {} : {}", sound_path.to_string_lossy(), e))?;
+    let source = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Cannot decode {} : {}", sound_path.to_string_lossy(), e))?;
+    Ok(Box::new(source.convert_samples::<f32>()))
+}
+
+/// Several files played as one sound. Every layer is converted to the channels and
+/// sample rate of the first one so the samples can be summed frame by frame.
+pub struct Layered {
+    layers: Vec<BoxedSource>,
+    channels: u16,
+    sample_rate: u32,
+    total_duration: Option<Duration>,
+}
+
+impl Layered {
+    pub fn new(dir: &str, layers: &[Layer]) -> Result<Self, String> {
+        let mut opened = Vec::new();
+        for layer in layers {
+            opened.push((open_file(dir, &layer.file)?, layer));
+        }
+        let Some((first, _)) = opened.first() else {
+            return Err("A layered cue needs at least one layer".to_owned());
+        };
+        let (channels, sample_rate) = (first.channels(), first.sample_rate());
+        let total_duration = opened
+            .iter()
+            .map(|(source, layer)| source.total_duration().map(|d| d + layer.offset))
+            .try_fold(Duration::ZERO, |max, d| d.map(|d| max.max(d)));
+        let layers = opened
+            .into_iter()
+            .map(|(source, layer)| -> BoxedSource {
+                Box::new(
+                    UniformSourceIterator::<BoxedSource, f32>::new(source, channels, sample_rate)
+                        .amplify(layer.level)
+                        .delay(layer.offset),
+                )
+            })
+            .collect();
+        Ok(Self {
+            layers,
+            channels,
+            sample_rate,
+            total_duration,
+        })
+    }
+}
+
+impl Iterator for Layered {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let mut playing = false;
+        let mut sample = 0.0;
+        for layer in &mut self.layers {
+            if let Some(s) = layer.next() {
+                playing = true;
+                sample += s;
+            }
+        }
+        playing.then_some(sample)
+    }
+}
+
+impl Source for Layered {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        for layer in &mut self.layers {
+            layer.try_seek(pos)?;
+        }
+        Ok(())
+    }
+}
+
+/// Gain target shared with `Declick` sources, set from the thread that owns the sink
+#[derive(Clone)]
+pub struct DeclickHandle {
+    target: Arc<AtomicU32>,
+    /// Seconds to reach the target, as f32 bits
+    fade: Arc<AtomicU32>,
+}
+
+impl DeclickHandle {
+    pub fn new(fade: Duration) -> Self {
+        Self {
+            target: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            fade: Arc::new(AtomicU32::new(fade.as_secs_f32().to_bits())),
+        }
+    }
+
+    pub fn set(&self, gain: f32) {
+        self.target.store(gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Move to the gain over another duration than the de-click fade, like a panic fade out
+    pub fn fade_to(&self, gain: f32, fade: Duration) {
+        self.fade
+            .store(fade.as_secs_f32().to_bits(), Ordering::Relaxed);
+        self.set(gain);
+    }
+}
+
+/// Moves the gain toward the handle target a little on every sample, pausing after it
+/// reaches zero doesn't click like `Sink::pause` alone
+pub struct Declick {
+    source: BoxedSource,
+    gain: f32,
+    target: Arc<AtomicU32>,
+    fade: Arc<AtomicU32>,
+}
+
+impl Declick {
+    pub fn new(source: BoxedSource, handle: &DeclickHandle) -> Self {
+        Self {
+            source,
+            gain: f32::from_bits(handle.target.load(Ordering::Relaxed)),
+            target: Arc::clone(&handle.target),
+            fade: Arc::clone(&handle.fade),
+        }
+    }
+}
+
+impl Iterator for Declick {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let target = f32::from_bits(self.target.load(Ordering::Relaxed));
+        if self.gain != target {
+            let samples = f32::from_bits(self.fade.load(Ordering::Relaxed))
+                * self.source.sample_rate() as f32
+                * self.source.channels() as f32;
+            let step = if samples < 1.0 { 1.0 } else { 1.0 / samples };
+            self.gain = if self.gain < target {
+                (self.gain + step).min(target)
+            } else {
+                (self.gain - step).max(target)
+            };
+        }
+        self.source.next().map(|s| s * self.gain)
+    }
+}
+
+impl Source for Declick {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.source.try_seek(pos)
+    }
+}
+
+/// How often the playlist looks at its messages and volumes
+const PLAYLIST_TICK: Duration = Duration::from_millis(20);
+
+/// Linear gain change over time
+#[derive(Clone, Copy)]
+struct Ramp {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Ramp {
+    fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    fn value(&self) -> f32 {
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let progress =
+            (self.start.elapsed().as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0);
+        self.from + (self.to - self.from) * progress
+    }
+
+    fn is_done(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+
+    /// Hold the ramp where it is for the time the playlist was paused
+    fn delay(&mut self, paused: Duration) {
+        self.start += paused;
+    }
+}
+
+/// A playlist track on its own sink so two of them can overlap during a crossfade
+struct Deck {
+    sink: Sink,
+    track: usize,
+    ramp: Ramp,
+    /// Set once the next track has been started or the end fade has begun
+    leaving: bool,
+}
+
+/// Everything the playlist thread needs, copied from the sound list when it starts
+pub struct Playlist {
+    pub dir: String,
+    pub tracks: Vec<SoundItem>,
+    pub shuffle: bool,
+    pub crossfade: Duration,
+    pub general_volume: f32,
+    /// Gain of every bus, indexed like `SoundList::buses`
+    pub bus_gains: Vec<f32>,
+    /// Name of the track currently heard, shown in the sound list
+    pub now_playing: Arc<Mutex<String>>,
+    /// De-click fade before pausing and after resuming
+    pub pause_fade: Duration,
+    pub declick: DeclickHandle,
+}
+
+impl Playlist {
+    /// Play the tracks one after another with crossfades until stopped
+    pub fn run(self, receiver: Receiver<PlaylistState>) {
+        let Ok((_stream, stream_handle)) = open_output() else {
+            return;
+        };
+        self.play(receiver, || Sink::try_new(&stream_handle).ok());
+    }
+
+    /// The playlist loop, every track plays on a sink of `new_sink`
+    fn play(mut self, receiver: Receiver<PlaylistState>, new_sink: impl Fn() -> Option<Sink>) {
+        let mut order = self.order();
+        let mut next_in_order = 0;
+        let mut decks: Vec<Deck> = Vec::new();
+        let mut stop_at_end = false;
+        let mut stopping = false;
+        // Failing tracks are skipped, give up when none of them can be opened
+        let mut failures = 0;
+        let mut pausing_until: Option<Instant> = None;
+        let mut paused_at: Option<Instant> = None;
+        let mut fade_out: Option<Duration> = None;
+        // Once the sender is gone the channel is not polled, the loop just fades out
+        let mut disconnected = false;
+
+        loop {
+            let state = if disconnected {
+                thread::sleep(PLAYLIST_TICK);
+                Err(mpsc::RecvTimeoutError::Timeout)
+            } else {
+                receiver.recv_timeout(PLAYLIST_TICK)
+            };
+            match state {
+                Ok(PlaylistState::Pause) => {
+                    if paused_at.is_none() && pausing_until.is_none() {
+                        self.declick.set(0.0);
+                        pausing_until = Some(Instant::now() + self.pause_fade);
+                    }
+                }
+                Ok(PlaylistState::Resume) => {
+                    if let Some(paused_at) = paused_at.take() {
+                        for deck in &mut decks {
+                            deck.ramp.delay(paused_at.elapsed());
+                            deck.sink.play();
+                        }
+                    }
+                    pausing_until = None;
+                    self.declick.set(1.0);
+                }
+                Ok(PlaylistState::Skip) if !stopping && paused_at.is_none() => {
+                    self.start_next(&new_sink, &mut decks, &mut order, &mut next_in_order);
+                }
+                Ok(PlaylistState::Skip) => {}
+                Ok(PlaylistState::FadeOutAtEnd) => stop_at_end = !stop_at_end,
+                Ok(PlaylistState::Stop) if !stopping => fade_out = Some(self.crossfade),
+                Ok(PlaylistState::Stop) => {}
+                // A panic fade is never lengthened by the stop that follows it
+                Ok(PlaylistState::FadeOut(fade)) => fade_out = Some(fade),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    disconnected = true;
+                    if !stopping {
+                        fade_out = Some(self.crossfade);
+                    }
+                }
+                Ok(PlaylistState::VolumeChanged(volume)) => self.general_volume = volume,
+                Ok(PlaylistState::BusVolumeChanged(bus_gains)) => self.bus_gains = bus_gains,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Some(fade) = fade_out.take() {
+                if paused_at.is_some() {
+                    // Already silent
+                    break;
+                }
+                stopping = true;
+                for deck in &mut decks {
+                    deck.ramp = Ramp::new(deck.ramp.value(), 0.0, fade);
+                    deck.leaving = true;
+                }
+            }
+            if pausing_until.is_some_and(|until| Instant::now() >= until) {
+                pausing_until = None;
+                paused_at = Some(Instant::now());
+                for deck in &decks {
+                    deck.sink.pause();
+                }
+            }
+            if paused_at.is_some() {
+                continue;
+            }
+
+            if decks.is_empty() && !stopping {
+                if failures > self.tracks.len() {
+                    break;
+                }
+                if self.start_next(&new_sink, &mut decks, &mut order, &mut next_in_order) {
+                    failures = 0;
+                } else {
+                    failures += 1;
+                    continue;
+                }
+            }
+
+            // Start the crossfade when the current track reaches its last seconds
+            if let Some(current) = decks.last_mut() {
+                let end = self.tracks[current.track].end();
+                let remaining = end.saturating_sub(current.sink.get_pos());
+                if !current.leaving && !stopping && remaining <= self.crossfade {
+                    if stop_at_end {
+                        stopping = true;
+                        current.leaving = true;
+                        current.ramp = Ramp::new(current.ramp.value(), 0.0, remaining);
+                    } else {
+                        self.start_next(&new_sink, &mut decks, &mut order, &mut next_in_order);
+                    }
+                }
+            }
+
+            decks.retain(|deck| {
+                let faded_out = (deck.leaving && deck.ramp.is_done() && deck.ramp.to == 0.0)
+                    || deck.sink.get_pos() >= self.tracks[deck.track].end();
+                if faded_out {
+                    deck.sink.stop();
+                }
+                !faded_out && !deck.sink.empty()
+            });
+            for deck in &decks {
+                let item = &self.tracks[deck.track];
+                let bus = self.bus_gains.get(item.bus).copied().unwrap_or(1.0);
+                deck.sink.set_volume(
+                    mixed_volume(self.general_volume, item.local_volume, bus) * deck.ramp.value(),
+                );
+            }
+            if let Ok(mut now_playing) = self.now_playing.lock() {
+                *now_playing = decks
+                    .last()
+                    .map(|deck| self.tracks[deck.track].name.clone())
+                    .unwrap_or_default();
+            }
+            if stopping && decks.is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// Fade the current track out and the next one in, returns false if the next track cannot be opened
+    fn start_next(
+        &self,
+        new_sink: &impl Fn() -> Option<Sink>,
+        decks: &mut Vec<Deck>,
+        order: &mut Vec<usize>,
+        next_in_order: &mut usize,
+    ) -> bool {
+        if self.tracks.is_empty() {
+            return false;
+        }
+        if *next_in_order >= order.len() {
+            // Keep going until stopped
+            *order = self.order();
+            *next_in_order = 0;
+        }
+        let track = order[*next_in_order];
+        *next_in_order += 1;
+        let item = &self.tracks[track];
+        let Some(sink) = new_sink() else {
+            return false;
+        };
+        let Ok(source) = open_item(&self.dir, item) else {
+            return false;
+        };
+        sink.set_volume(0.0);
+        sink.append(Declick::new(source, &self.declick));
+        let _ = sink.try_seek(item.trim_in);
+        for deck in decks.iter_mut() {
+            if !deck.leaving {
+                deck.leaving = true;
+                deck.ramp = Ramp::new(deck.ramp.value(), 0.0, self.crossfade);
+            }
+        }
+        decks.push(Deck {
+            sink,
+            track,
+            ramp: Ramp::new(0.0, 1.0, self.crossfade),
+            leaving: false,
+        });
+        true
+    }
+
+    fn order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.tracks.len()).collect();
+        if self.shuffle {
+            shuffle(&mut order);
+        }
+        order
+    }
+}
+
+/// Fisher-Yates shuffle seeded from the clock, good enough for an interval playlist
+fn shuffle(order: &mut [usize]) {
+    let mut seed = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x2545_f491_4f6c_dd1d)
+        | 1;
+    for i in (1..order.len()).rev() {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        order.swap(i, (seed % (i as u64 + 1)) as usize);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+    use std::sync::atomic::AtomicBool;
+
+    /// Write a mono 16 bit wav file filled with a constant sample
+    fn write_wav(dir: &std::path::Path, name: &str, sample: i16, frames: usize) {
+        let data_len = (frames * 2) as u32;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&8000u32.to_le_bytes());
+        bytes.extend_from_slice(&16000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for _ in 0..frames {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        File::create(dir.join(name))
+            .unwrap()
+            .write_all(&bytes)
+            .unwrap();
+    }
+
+    /// Play a long track on sinks without an output device, stop it once it is heard and return
+    /// how long the playlist thread lived after that
+    fn stop_playlist(
+        name: &str,
+        crossfade: Duration,
+        stop: impl FnOnce(mpsc::Sender<PlaylistState>),
+    ) -> Duration {
+        let dir = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        write_wav(&dir, "loop.wav", i16::MAX / 4, 80000);
+        let playlist = Playlist {
+            dir: dir.to_string_lossy().into_owned(),
+            tracks: vec![SoundItem::new(
+                "loop.wav".to_owned(),
+                Duration::from_secs(10),
+            )],
+            shuffle: false,
+            crossfade,
+            general_volume: 1.0,
+            bus_gains: Vec::new(),
+            now_playing: Arc::default(),
+            pause_fade: Duration::from_millis(10),
+            declick: DeclickHandle::new(Duration::from_millis(10)),
+        };
+        let now_playing = Arc::clone(&playlist.now_playing);
+        let done = Arc::new(AtomicBool::new(false));
+        let pulling = Arc::clone(&done);
+        let (sender, receiver) = mpsc::channel();
+        let player = thread::spawn(move || {
+            playlist.play(receiver, || {
+                let (sink, mut output) = Sink::new_idle();
+                let done = Arc::clone(&pulling);
+                // Pulled at the 8 kHz of the track
+                thread::spawn(move || {
+                    while !done.load(Ordering::Relaxed) {
+                        output.by_ref().take(80).for_each(drop);
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                });
+                Some(sink)
+            })
+        });
+        while now_playing.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let stopped = Instant::now();
+        stop(sender);
+        while !player.is_finished() && stopped.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let lived = stopped.elapsed();
+        done.store(true, Ordering::Relaxed);
+        std::fs::remove_dir_all(&dir).unwrap();
+        lived
+    }
+
+    #[test]
+    fn playlist_fades_out_once_its_sender_is_dropped() {
+        let crossfade = Duration::from_millis(200);
+        let lived = stop_playlist("utilscord_playlist_stop_test", crossfade, |sender| {
+            sender.send(PlaylistState::Stop).unwrap();
+        });
+        assert!(
+            lived < crossfade + Duration::from_millis(500),
+            "{:?}",
+            lived
+        );
+    }
+
+    #[test]
+    fn panic_silences_the_playlist_within_the_panic_fade() {
+        let panic_fade = super::super::SoundList::from_dir(String::new()).panic_fade;
+        // Like TabManager::panic, the fade is sent and the sender dropped
+        let lived = stop_playlist(
+            "utilscord_playlist_panic_test",
+            Duration::from_secs(5),
+            |sender| {
+                sender.send(PlaylistState::FadeOut(panic_fade)).unwrap();
+            },
+        );
+        assert!(
+            lived < panic_fade + Duration::from_millis(300),
+            "{:?}",
+            lived
+        );
+    }
+
+    #[test]
+    fn layers_are_summed_with_level_and_offset() {
+        let dir = std::env::temp_dir().join("utilscord_layered_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        write_wav(&dir, "a.wav", i16::MAX / 4, 800);
+        write_wav(&dir, "b.wav", i16::MAX / 4, 800);
+        let layers = vec![
+            Layer {
+                file: "a.wav".to_owned(),
+                level: 1.0,
+                offset: Duration::ZERO,
+                duration: Duration::from_millis(100),
+            },
+            Layer {
+                file: "b.wav".to_owned(),
+                level: 0.5,
+                offset: Duration::from_millis(50),
+                duration: Duration::from_millis(100),
+            },
+        ];
+        let samples: Vec<f32> = Layered::new(dir.to_str().unwrap(), &layers)
+            .unwrap()
+            .collect();
+        // b starts 400 frames later and ends 400 frames after a
+        assert_eq!(samples.len(), 1200);
+        assert!((samples[0] - 0.25).abs() < 0.01);
+        assert!((samples[600] - 0.375).abs() < 0.01);
+        assert!((samples[1000] - 0.125).abs() < 0.01);
+    }
+
+    #[test]
+    fn shuffle_keeps_every_track() {
+        let mut order: Vec<usize> = (0..20).collect();
+        shuffle(&mut order);
+        order.sort();
+        assert_eq!(order, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn silence_is_detected_at_both_ends() {
+        let dir = std::env::temp_dir().join("utilscord_silence_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(dir.join("speech.wav"), spec).unwrap();
+        for i in 0..14000 {
+            // Noise floor around -60 dB, the sound from 0.5s to 1.5s
+            let sample = if (4000..12000).contains(&i) { 8000 } else { 30 };
+            writer.write_sample(sample as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut item = SoundItem::new("speech.wav".to_owned(), Duration::from_millis(1750));
+        let dir_str = dir.to_string_lossy();
+        let (trim_in, trim_out) = detect_sound(&dir_str, &item, -50.0).unwrap().unwrap();
+        assert_eq!(trim_in, Duration::from_millis(500));
+        assert_eq!(trim_out, Duration::from_millis(1500));
+        assert_eq!(detect_sound(&dir_str, &item, -6.0).unwrap(), None);
+
+        item.suggest_trim(trim_in, trim_out);
+        assert!(item.has_trim_suggestion());
+        assert_eq!(item.trim_in, Duration::ZERO);
+        item.apply_trim_inputs();
+        assert!(!item.has_trim_suggestion());
+        assert_eq!(item.trim_in, Duration::from_millis(500));
+        assert_eq!(item.trim_out, Some(Duration::from_millis(1500)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn declick_ramps_to_the_target() {
+        let handle = DeclickHandle::new(Duration::from_millis(10));
+        let source: BoxedSource =
+            Box::new(rodio::buffer::SamplesBuffer::new(1, 1000, vec![1.0f32; 40]));
+        let mut declick = Declick::new(source, &handle);
+        assert_eq!(declick.next(), Some(1.0));
+        handle.set(0.0);
+        let fading: Vec<f32> = declick.by_ref().take(10).collect();
+        assert!(fading.windows(2).all(|w| w[1] < w[0]));
+        assert!(fading[9].abs() < 1e-4);
+        handle.set(1.0);
+        let samples: Vec<f32> = declick.collect();
+        assert!((samples[9] - 1.0).abs() < 1e-4);
+        assert!((samples.last().unwrap() - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,280 @@
+use super::{audio, mixed_volume, SoundItem};
+use rodio::Source;
+use std::fs::{self, File};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Samples per FLAC frame
+const FLAC_BLOCK_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BounceFormat {
+    Wav,
+    Flac,
+}
+
+impl BounceFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BounceFormat::Wav => "wav",
+            BounceFormat::Flac => "flac",
+        }
+    }
+}
+
+/// Where the bounce of an item is written : a Bounce folder next to the sound files
+pub fn bounce_path(dir: &str, item: &SoundItem, format: BounceFormat) -> PathBuf {
+    let stem = Path::new(&item.name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| item.name.clone());
+    let mut path = PathBuf::from(dir);
+    path.push("Bounce");
+    path.push(format!("{}.{}", stem, format.extension()));
+    path
+}
+
+/// Render the sound item to a 16 bit file with its trims, fades, local volume and layers baked in.
+/// The general volume and the buses are left out, they belong to the venue and not to the sound.
+pub fn bounce(
+    dir: &str,
+    item: &SoundItem,
+    path: &Path,
+    format: BounceFormat,
+) -> Result<Duration, String> {
+    let (fade_in, fade_out) = item.fades();
+    let mut source = audio::playback_source(dir, item, fade_in, fade_out)?;
+    source
+        .try_seek(item.trim_in)
+        .map_err(|e| format!("Cannot seek {} to its trim in : {}", item.name, e))?;
+    let channels = source.channels();
+    let sample_rate = source.sample_rate();
+    let gain = mixed_volume(1.0, item.local_volume, 1.0);
+    let frames = (item.end().saturating_sub(item.trim_in).as_secs_f64() * sample_rate as f64)
+        .round() as usize;
+    // Samples go to the file as they are decoded, a long sound is never held in memory
+    let samples = source
+        .take(frames * channels as usize)
+        .map(|s| ((s * gain).clamp(-1.0, 1.0) * i16::MAX as f32) as i16);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Cannot create {} : {}", parent.to_string_lossy(), e))?;
+    }
+    let written = match format {
+        BounceFormat::Wav => write_wav(path, channels, sample_rate, samples)?,
+        BounceFormat::Flac => write_flac(path, channels, sample_rate, frames, samples)?,
+    };
+    Ok(Duration::from_secs_f64(
+        written as f64 / channels as f64 / sample_rate as f64,
+    ))
+}
+
+/// Returns the number of samples written
+fn write_wav(
+    path: &Path,
+    channels: u16,
+    sample_rate: u32,
+    samples: impl Iterator<Item = i16>,
+) -> Result<usize, String> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let error = |e: hound::Error| format!("Cannot write {} : {}", path.to_string_lossy(), e);
+    let mut writer = hound::WavWriter::create(path, spec).map_err(error)?;
+    let mut written = 0;
+    for sample in samples {
+        writer.write_sample(sample).map_err(error)?;
+        written += 1;
+    }
+    writer.finalize().map_err(error)?;
+    Ok(written)
+}
+
+/// Uncompressed FLAC : every subframe is verbatim, which keeps the encoder byte aligned. The header
+/// is written with the expected `frames` and corrected at the end if the source was shorter.
+/// Returns the number of samples written
+fn write_flac(
+    path: &Path,
+    channels: u16,
+    sample_rate: u32,
+    frames: usize,
+    mut samples: impl Iterator<Item = i16>,
+) -> Result<usize, String> {
+    if !(1..=8).contains(&channels) {
+        return Err(format!("FLAC cannot hold {} channels", channels));
+    }
+    let error = |e: std::io::Error| format!("Cannot write {} : {}", path.to_string_lossy(), e);
+    let mut out = BufWriter::new(File::create(path).map_err(error)?);
+
+    let mut header = Vec::with_capacity(42);
+    header.extend_from_slice(b"fLaC");
+    // Last metadata block, STREAMINFO, 34 bytes
+    header.extend_from_slice(&[0x80, 0, 0, 34]);
+    header.extend_from_slice(&(FLAC_BLOCK_SIZE as u16).to_be_bytes());
+    header.extend_from_slice(&(FLAC_BLOCK_SIZE as u16).to_be_bytes());
+    // Unknown min and max frame size
+    header.extend_from_slice(&[0; 6]);
+    header.extend_from_slice(&stream_format(channels, sample_rate, frames).to_be_bytes());
+    // Unknown MD5
+    header.extend_from_slice(&[0; 16]);
+    out.write_all(&header).map_err(error)?;
+
+    let mut written = 0;
+    for number in 0.. {
+        let block: Vec<i16> = samples
+            .by_ref()
+            .take(FLAC_BLOCK_SIZE * channels as usize)
+            .collect();
+        if block.is_empty() {
+            break;
+        }
+        written += block.len();
+        let block_size = block.len() / channels as usize;
+        let mut frame = vec![0xFF, 0xF8];
+        // Block size in 16 bits at the end of the header, sample rate from STREAMINFO
+        frame.push(0x70);
+        // Independent channels, 16 bits per sample
+        frame.push((((channels - 1) as u8) << 4) | 0x08);
+        push_utf8_number(&mut frame, number as u32);
+        frame.extend_from_slice(&((block_size - 1) as u16).to_be_bytes());
+        frame.push(crc8(&frame));
+        for channel in 0..channels as usize {
+            // Verbatim subframe without wasted bits
+            frame.push(0x02);
+            for sample in block.iter().skip(channel).step_by(channels as usize) {
+                frame.extend_from_slice(&sample.to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(&crc16(&frame).to_be_bytes());
+        out.write_all(&frame).map_err(error)?;
+    }
+    let written_frames = written / channels as usize;
+    if written_frames != frames {
+        // The format follows the marker, the block header and the block and frame sizes
+        out.seek(SeekFrom::Start(18)).map_err(error)?;
+        out.write_all(&stream_format(channels, sample_rate, written_frames).to_be_bytes())
+            .map_err(error)?;
+    }
+    out.flush().map_err(error)?;
+    Ok(written)
+}
+
+/// Sample rate (20 bits), channels - 1 (3 bits), bits per sample - 1 (5 bits), total samples (36 bits)
+fn stream_format(channels: u16, sample_rate: u32, frames: usize) -> u64 {
+    ((sample_rate as u64) << 44)
+        | (((channels - 1) as u64) << 41)
+        | (15 << 36)
+        | (frames as u64 & 0xF_FFFF_FFFF)
+}
+
+/// Frame numbers are coded like UTF-8 characters
+fn push_utf8_number(out: &mut Vec<u8>, number: u32) {
+    if number < 0x80 {
+        out.push(number as u8);
+        return;
+    }
+    let continuation_bytes = match number {
+        0..=0x7FF => 1,
+        0x800..=0xFFFF => 2,
+        0x1_0000..=0x1F_FFFF => 3,
+        0x20_0000..=0x3FF_FFFF => 4,
+        _ => 5,
+    };
+    let marker = !(0xFFu8 >> (continuation_bytes + 1));
+    out.push(marker | (number >> (6 * continuation_bytes)) as u8);
+    for i in (0..continuation_bytes).rev() {
+        out.push(0x80 | ((number >> (6 * i)) & 0x3F) as u8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, byte| {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rodio::Decoder;
+    use std::io::BufReader;
+
+    fn decode(path: &Path) -> Vec<i16> {
+        Decoder::new(BufReader::new(File::open(path).unwrap()))
+            .unwrap()
+            .collect()
+    }
+
+    #[test]
+    fn bounce_bakes_local_volume_and_trim() {
+        let dir = std::env::temp_dir().join("utilscord_bounce_test");
+        fs::create_dir_all(&dir).unwrap();
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(dir.join("tone.wav"), spec).unwrap();
+        for _ in 0..10000 * 2 {
+            writer.write_sample(16000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut item = SoundItem::new("tone.wav".to_owned(), Duration::from_millis(1250));
+        item.local_volume = -0.5;
+        item.trim_in = Duration::from_millis(250);
+        let dir_str = dir.to_string_lossy();
+        for format in [BounceFormat::Wav, BounceFormat::Flac] {
+            let path = bounce_path(&dir_str, &item, format);
+            let length = bounce(&dir_str, &item, &path, format).unwrap();
+            assert_eq!(length, Duration::from_secs(1));
+            let samples = decode(&path);
+            assert_eq!(samples.len(), 8000 * 2);
+            assert!(
+                samples.iter().all(|s| (*s - 8000).abs() <= 1),
+                "{:?}",
+                format
+            );
+        }
+
+        // The sound ends before the item, the header counts what was written
+        let mut item = SoundItem::new("tone.wav".to_owned(), Duration::from_secs(2));
+        item.trim_in = Duration::from_millis(250);
+        let path = bounce_path(&dir_str, &item, BounceFormat::Flac);
+        let length = bounce(&dir_str, &item, &path, BounceFormat::Flac).unwrap();
+        assert_eq!(length, Duration::from_secs(1));
+        let header = fs::read(&path).unwrap();
+        let format = u64::from_be_bytes(header[18..26].try_into().unwrap());
+        assert_eq!(format, stream_format(2, 8000, 8000));
+        assert_eq!(decode(&path).len(), 8000 * 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,24 +1,33 @@
+#[path = "audio.rs"]
+pub mod audio;
+#[path = "bounce.rs"]
+pub mod bounce;
+#[path = "cuelist.rs"]
+pub mod cuelist;
 #[path = "render.rs"]
 mod render;
+use super::learn::Learn;
+use super::monitor::Monitor;
+use super::osc_send::{CueMessage, Destination, Outputs};
+use super::osc_tcp::{self, TcpClients};
 use core::panic;
 use lofty::file::AudioFile;
 use ratatui::prelude::*;
 use ratatui::widgets::*;
-use rodio::Source;
-use rodio::{Decoder, OutputStream, Sink};
+use rodio::Sink;
 use rosc::OscPacket;
-use std::fs::{self, File};
-use std::io::BufReader;
-use std::net::{SocketAddrV4, UdpSocket};
-use std::path::Path;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug)]
 pub struct Tab {
@@ -59,10 +68,13 @@ impl Tab {
                 if listening_ip_input.edit_mode {
                     return true;
                 }
-
-                false
+                let learn = &listening_ip_input.learn;
+                learn.picking.is_some()
+                    || learn.editing.is_some()
+                    || listening_ip_input.test_message.is_some()
             }
             Content::Dmx(..) => false,
+            Content::Monitor(monitor) => monitor.filtering,
         }
     }
 
@@ -86,6 +98,7 @@ impl Tab {
                 dmx_array[index].is_focused = false;
                 dmx_array[(index + 1) % array_len].is_focused = true;
             }
+            Content::Monitor(_) => (),
         }
     }
 
@@ -109,6 +122,7 @@ impl Tab {
                 dmx_array[index].is_focused = false;
                 dmx_array[(index + array_len - 1) % array_len].is_focused = true;
             }
+            Content::Monitor(_) => (),
         }
     }
 }
@@ -126,6 +140,7 @@ pub enum Content {
         String,
         String,
     ),
+    Monitor(Monitor),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -154,6 +169,7 @@ impl Content {
             Content::MainMenu(_sound_list, _input) => "Sound Bank",
             Content::Osc(_listening_ip_input) => "OSC",
             Content::Dmx(_, _, _, _, _, _, _) => "DMX",
+            Content::Monitor(_monitor) => "OSC Monitor",
         }
     }
 }
@@ -243,6 +259,18 @@ pub struct IPInput {
     pub title: String,
     character_index: usize,
     pub _osc_receiver: Option<OscPacket>,
+    /// Clients connected to the TCP addresses, replaced on every listen
+    pub tcp_clients: TcpClients,
+    /// Threads of the current listen, stopped before the next one binds
+    listeners: Listeners,
+    /// Addresses of remote devices mapped to actions, focused when the address is not
+    pub learn: Learn,
+    /// Show systems the cues send OSC to
+    pub destinations: Vec<Destination>,
+    /// The destinations resolved when the show loads
+    pub outputs: Outputs,
+    /// Message typed to test a destination, None when the test box is closed
+    pub test_message: Option<String>,
     last_action_widget: OscInfoWidget,
     style: Style,
 }
@@ -253,10 +281,18 @@ impl IPInput {
         IPInput {
             title,
             focus: false,
-            input: String::from("Enter a valid IP:Port (e.g 192.168.1.50:8000)"),
+            input: String::from(
+                "Enter valid IP:Port separated by commas (e.g 192.168.1.50:8000, [::]:8000)",
+            ),
             edit_mode: false,
             character_index: 0,
             _osc_receiver: None,
+            tcp_clients: TcpClients::default(),
+            listeners: Listeners::default(),
+            learn: Learn::default(),
+            destinations: Vec::new(),
+            outputs: Outputs::default(),
+            test_message: None,
             last_action_widget: OscInfoWidget::new(),
             style: Style::default().fg(Color::Yellow),
         }
@@ -266,7 +302,7 @@ impl IPInput {
         self.last_action_widget.update(new_info.into());
     }
 
-    pub fn toggle_edit_mode(&mut self) -> Result<Receiver<OscPacket>, ()> {
+    pub fn toggle_edit_mode(&mut self) -> Result<Receiver<OscReceived>, ()> {
         if self.edit_mode {
             self.edit_mode = false;
             match self.submit_message() {
@@ -326,46 +362,213 @@ impl IPInput {
         self.character_index = 0;
     }
 
-    fn submit_message(&mut self) -> Result<Receiver<OscPacket>, ()> {
-        self.reset_cursor();
-        let addr = SocketAddrV4::from_str(&self.input);
-        match addr {
-            Ok(valid_adress) => match UdpSocket::bind(valid_adress) {
-                Ok(running_socket) => {
-                    let (sender, receiver) = mpsc::channel();
+    /// Bind the OSC listener to the address in the input
+    pub fn listen(&mut self) -> Result<Receiver<OscReceived>, ()> {
+        self.submit_message()
+    }
 
-                    thread::spawn(move || {
+    /// Every address of the input is bound, the packets of all of them come out of one receiver
+    fn submit_message(&mut self) -> Result<Receiver<OscReceived>, ()> {
+        self.reset_cursor();
+        // The previous listen keeps running until every new address is bound, the ports it holds
+        // are bound again once it is stopped
+        let sockets = listen_addresses(&self.input).and_then(|addresses| {
+            let mut sockets = Vec::new();
+            let mut held = Vec::new();
+            for addr in &addresses {
+                match addr.bind() {
+                    Ok(socket) => sockets.push(socket),
+                    Err(e) if e.kind() == ErrorKind::AddrInUse && self.listeners.holds(*addr) => {
+                        held.push(*addr)
+                    }
+                    Err(e) => return Err(format!("{} : {}", addr, e)),
+                }
+            }
+            self.listeners.stop();
+            for addr in held {
+                sockets.push(addr.bind().map_err(|e| format!("{} : {}", addr, e))?);
+            }
+            self.listeners = Listeners {
+                addresses,
+                ..Listeners::default()
+            };
+            Ok(sockets)
+        });
+        match sockets {
+            Ok(sockets) => {
+                let (sender, receiver) = mpsc::channel();
+                self.tcp_clients = TcpClients::default();
+                for socket in sockets {
+                    let stop = Arc::clone(&self.listeners.stop);
+                    let running_socket = match socket {
+                        OscSocket::Udp(running_socket) => running_socket,
+                        OscSocket::Tcp(listener) => {
+                            self.listeners.add(osc_tcp::serve(
+                                listener,
+                                sender.clone(),
+                                self.tcp_clients.clone(),
+                                stop,
+                            ));
+                            continue;
+                        }
+                    };
+                    let _ = running_socket.set_read_timeout(Some(osc_tcp::POLL));
+                    let sender = sender.clone();
+                    self.listeners.add(thread::spawn(move || {
                         let mut buf = [0u8; rosc::decoder::MTU];
-                        loop {
+                        while !stop.load(Ordering::Relaxed) {
                             match running_socket.recv_from(&mut buf) {
-                                Ok((size, _addr)) => {
+                                Ok((size, from)) => {
                                     match rosc::decoder::decode_udp(&buf[..size]) {
-                                        Ok((_, packet)) => if sender.send(packet).is_ok() {},
+                                        Ok((_, packet)) => {
+                                            if sender.send(OscReceived { from, packet }).is_ok() {}
+                                        }
                                         Err(_e) => {}
                                     };
                                 }
                                 Err(_e) => {}
                             }
                         }
-                    });
-                    self.style = Style::default().fg(Color::LightGreen);
-                    Ok(receiver)
+                    }));
                 }
-                Err(socket_error) => {
-                    self.style = Style::default().fg(Color::LightRed);
-                    self.input = format!("{}", socket_error);
-                    Err(())
-                }
-            },
-            Err(invalid_adress) => {
+                self.style = Style::default().fg(Color::LightGreen);
+                Ok(receiver)
+            }
+            Err(error) => {
                 self.style = Style::default().fg(Color::LightRed);
-                self.input = format!("{}", invalid_adress);
+                self.input = error;
                 Err(())
             }
         }
     }
 }
 
+/// Threads receiving for the OSC listener, shared by the clones of the OSC tab
+#[derive(Clone, Debug, Default)]
+struct Listeners {
+    /// Addresses they were bound to
+    addresses: Vec<ListenAddress>,
+    stop: Arc<AtomicBool>,
+    threads: Arc<Mutex<Vec<thread::JoinHandle<()>>>>,
+}
+
+impl Listeners {
+    /// The port of the address is taken by these listeners
+    fn holds(&self, addr: ListenAddress) -> bool {
+        self.addresses.iter().any(|held| match (held, addr) {
+            (ListenAddress::Udp(held), ListenAddress::Udp(addr))
+            | (ListenAddress::Tcp(held), ListenAddress::Tcp(addr)) => held.port() == addr.port(),
+            _ => false,
+        })
+    }
+
+    fn add(&self, thread: thread::JoinHandle<()>) {
+        if let Ok(mut threads) = self.threads.lock() {
+            threads.push(thread);
+        }
+    }
+
+    /// Wait for every thread to end, their sockets are closed when this returns
+    fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let threads = self
+            .threads
+            .lock()
+            .map(|mut threads| std::mem::take(&mut *threads))
+            .unwrap_or_default();
+        for thread in threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A packet of the OSC listener and where it came from
+#[derive(Debug)]
+pub struct OscReceived {
+    pub from: SocketAddr,
+    pub packet: OscPacket,
+}
+
+/// Address the OSC listener binds to, UDP unless written tcp://IP:Port
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListenAddress {
+    Udp(SocketAddr),
+    /// OSC 1.1 packets framed with SLIP, one connection per client
+    Tcp(SocketAddr),
+}
+
+enum OscSocket {
+    Udp(UdpSocket),
+    Tcp(TcpListener),
+}
+
+impl ListenAddress {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (tcp, addr) = match text.strip_prefix("tcp://") {
+            Some(addr) => (true, addr),
+            None => (false, text.strip_prefix("udp://").unwrap_or(text)),
+        };
+        let addr = addr
+            .parse::<SocketAddr>()
+            .map_err(|e| format!("{} : {}", text, e))?;
+        if !tcp {
+            Ok(ListenAddress::Udp(addr))
+        } else if addr.ip().is_multicast() {
+            Err(format!("{} : multicast is UDP only", text))
+        } else {
+            Ok(ListenAddress::Tcp(addr))
+        }
+    }
+
+    fn bind(self) -> std::io::Result<OscSocket> {
+        match self {
+            ListenAddress::Udp(addr) => bind_osc(addr).map(OscSocket::Udp),
+            ListenAddress::Tcp(addr) => TcpListener::bind(addr).map(OscSocket::Tcp),
+        }
+    }
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddress::Udp(addr) => write!(f, "{}", addr),
+            ListenAddress::Tcp(addr) => write!(f, "tcp://{}", addr),
+        }
+    }
+}
+
+/// IP:Port separated by commas, IPv6 in brackets like [::]:8000 and TCP like tcp://0.0.0.0:8000
+pub fn listen_addresses(input: &str) -> Result<Vec<ListenAddress>, String> {
+    let addresses = input
+        .split(',')
+        .map(str::trim)
+        .filter(|addr| !addr.is_empty())
+        .map(ListenAddress::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if addresses.is_empty() {
+        return Err("No IP:Port to listen to".to_owned());
+    }
+    Ok(addresses)
+}
+
+/// Bind to the address, a multicast group is bound on the port of every interface and joined on
+/// the default one, the interface the system routes the group to
+fn bind_osc(addr: SocketAddr) -> std::io::Result<UdpSocket> {
+    match addr.ip() {
+        IpAddr::V4(group) if group.is_multicast() => {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, addr.port()))?;
+            socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?;
+            Ok(socket)
+        }
+        IpAddr::V6(group) if group.is_multicast() => {
+            let socket = UdpSocket::bind((Ipv6Addr::UNSPECIFIED, addr.port()))?;
+            socket.join_multicast_v6(&group, 0)?;
+            Ok(socket)
+        }
+        _ => UdpSocket::bind(addr),
+    }
+}
+
 impl Clone for Content {
     fn clone(&self) -> Content {
         match self {
@@ -384,6 +587,7 @@ impl Clone for Content {
                     dmx_status.clone(),
                 )
             }
+            Content::Monitor(monitor) => Content::Monitor(monitor.clone()),
         }
     }
 }
@@ -394,25 +598,333 @@ pub struct SoundList {
     pub sound_files: Vec<SoundItem>,
     pub state: ListState,
     pub current_dir: String, // Store the current directory path
+    /// CSV the items were imported from, in its order, instead of the directory listing
+    pub cue_list: Option<PathBuf>,
     pub selected: bool,
     pub currently_playing: String,
     pub volume: f32, // General Volume
     pub editingfades: bool,
+    /// Output buses the sound items are routed to, the general volume is the master over them
+    pub buses: Vec<Bus>,
+    /// Position of the playing sound, updated by the playback thread
+    pub playhead: Arc<Mutex<Duration>>,
+    /// Items picked to be grouped in a layered cue
+    pub layer_selection: Vec<usize>,
+    pub playlist: PlaylistSettings,
+    /// Track heard in the background playlist, empty when it is not running
+    pub playlist_now_playing: Arc<Mutex<String>>,
+    /// Outcome of the last action without a view of its own, like a bounce
+    pub status: String,
+    /// Level in dBFS under which the start and the end of a sound are considered silent
+    pub silence_threshold: f32,
+    /// De-click fade before pausing and after resuming
+    pub pause_fade: Duration,
+    /// Fade out of every sound on panic
+    pub panic_fade: Duration,
+}
+
+/// Background playlist options, the group is the bus whose items are played
+#[derive(Clone, Debug)]
+pub struct PlaylistSettings {
+    pub shuffle: bool,
+    pub crossfade: Duration,
+    /// Only play the items routed to this bus, every item when None
+    pub group: Option<usize>,
+}
+
+impl Default for PlaylistSettings {
+    fn default() -> Self {
+        Self {
+            shuffle: false,
+            crossfade: Duration::from_secs(3),
+            group: None,
+        }
+    }
+}
+
+/// A submaster with its own fader, mute and solo
+#[derive(Clone, Debug)]
+pub struct Bus {
+    pub name: String,
+    pub volume: f32,
+    pub mute: bool,
+    pub solo: bool,
+}
+
+impl Bus {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            volume: 1.0,
+            mute: false,
+            solo: false,
+        }
+    }
+
+    pub fn default_buses() -> Vec<Bus> {
+        vec![Bus::new("Music"), Bus::new("SFX"), Bus::new("Voice")]
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct SoundItem {
     pub name: String,
+    /// Shown in the list instead of the file name, set by a cue list
+    pub display_name: Option<String>,
     pub selected: bool,
     pub local_volume: f32,
-    /// Fade In | Fade Out | Trim In
+    /// Fade In | Fade Out | Trim In | Trim Out | Add Marker | Notes | OSC Out | Layer Level |
+    /// Layer Offset ...
     edit_tab_selected: usize,
     pub fade_tab_content: Vec<Input>,
     pub trim_in: Duration,
+    /// Playback stops there, None plays to the end of the file
+    pub trim_out: Option<Duration>,
     pub max_duration: Duration,
+    /// Index of the bus in `SoundList::buses`
+    pub bus: usize,
+    /// Named positions, sorted by position
+    pub markers: Vec<Marker>,
+    selected_marker: usize,
+    /// Files mixed together when this item is a layered cue, empty for a plain sound file
+    pub layers: Vec<Layer>,
+    /// Sent to other show systems when the cue fires
+    pub osc: Vec<CueMessage>,
+}
+
+/// Index in `SoundItem::fade_tab_content` of the first layer input
+pub const LAYER_INPUTS_START: usize = 7;
+
+const OSC_OUT_TITLE: &str = "OSC Out (destination /address args ; ...)";
+
+/// A file of a layered cue with its own level and start offset
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub file: String,
+    pub level: f32,
+    pub offset: Duration,
+    /// Duration of the file alone
+    pub duration: Duration,
+}
+
+/// A named position inside a sound
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub name: String,
+    pub position: Duration,
+}
+
+impl Marker {
+    /// Parse `name@seconds`, ex : `Chorus 2@63.5`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let Some((name, position)) = text.rsplit_once('@') else {
+            return Err(format!("{} is not formatted as name@seconds", text));
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Marker name is empty".to_owned());
+        }
+        match position.trim().parse::<f32>() {
+            Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Ok(Self {
+                name: name.to_owned(),
+                position: Duration::from_secs_f32(seconds),
+            }),
+            _ => Err(format!("{} is not a valid position", position)),
+        }
+    }
 }
 
 impl SoundItem {
+    pub fn new(name: String, max_duration: Duration) -> Self {
+        Self {
+            name,
+            display_name: None,
+            selected: false,
+            local_volume: 0.0,
+            edit_tab_selected: 0,
+            fade_tab_content: vec![
+                Input {
+                    input_field_title: "Fade In Time".to_owned(),
+                    is_selected: true,
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: "Fade Out Time".to_owned(),
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: "Trim In".to_owned(),
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: "Trim Out".to_owned(),
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: "Add Marker (name@seconds)".to_owned(),
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: "Notes".to_owned(),
+                    ..Default::default()
+                },
+                Input {
+                    input_field_title: OSC_OUT_TITLE.to_owned(),
+                    ..Default::default()
+                },
+            ],
+            trim_in: Duration::from_secs(0),
+            trim_out: None,
+            max_duration,
+            bus: 0,
+            markers: Vec::new(),
+            selected_marker: 0,
+            layers: Vec::new(),
+            osc: Vec::new(),
+        }
+    }
+
+    /// Fade in and fade out typed in the edit tab, None when empty or zero
+    pub fn fades(&self) -> (Option<Duration>, Option<Duration>) {
+        let fade = |input: &Input| match input.input.trim().parse::<f32>().unwrap_or(0.0) {
+            x if x > 0.0 => Some(Duration::from_secs(x as u64)),
+            _ => None,
+        };
+        (
+            fade(&self.fade_tab_content[0]),
+            fade(&self.fade_tab_content[1]),
+        )
+    }
+
+    /// Display name, or the file name
+    pub fn title(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Free text for the stage manager, printed on the cue sheet
+    pub fn notes(&self) -> &str {
+        &self.fade_tab_content[5].input
+    }
+
+    pub fn set_notes(&mut self, notes: impl Into<String>) {
+        self.fade_tab_content[5].input = notes.into();
+        self.fade_tab_content[5].reset_cursor();
+    }
+
+    pub fn set_osc(&mut self, osc: Vec<CueMessage>) {
+        self.fade_tab_content[6].input = osc
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join(" ; ");
+        self.fade_tab_content[6].reset_cursor();
+        self.fade_tab_content[6].input_field_title = OSC_OUT_TITLE.to_owned();
+        self.osc = osc;
+    }
+
+    /// Read the OSC Out input, the messages are kept when one of them is wrong and the error is
+    /// shown in the title of the input
+    pub fn apply_osc_input(&mut self) -> Result<(), String> {
+        let osc = CueMessage::parse_list(&self.fade_tab_content[6].input).inspect_err(|e| {
+            self.fade_tab_content[6].input_field_title = format!("OSC Out - {}", e)
+        })?;
+        self.set_osc(osc);
+        Ok(())
+    }
+
+    /// Where the playback stops
+    pub fn end(&self) -> Duration {
+        self.trim_out.unwrap_or(self.max_duration)
+    }
+
+    /// Where a seek to `position` lands, between the trims so trimmed audio is never heard
+    pub fn seek_target(&self, position: Duration) -> Duration {
+        position.clamp(self.trim_in, self.end().max(self.trim_in))
+    }
+
+    /// Where a seek of `offset` seconds from `position` lands
+    pub fn seek_by(&self, position: Duration, offset: f32) -> Duration {
+        self.seek_target(Duration::from_secs_f32(
+            (position.as_secs_f32() + offset).max(0.0),
+        ))
+    }
+
+    /// Read the Trim In and Trim Out inputs, out of range values are clamped to the sound
+    pub fn apply_trim_inputs(&mut self) {
+        let seconds = |input: &Input| input.input.trim().parse::<f32>().ok().filter(|s| *s >= 0.0);
+        self.trim_in = seconds(&self.fade_tab_content[2])
+            .map(Duration::from_secs_f32)
+            .unwrap_or_default()
+            .min(self.max_duration);
+        self.trim_out = seconds(&self.fade_tab_content[3])
+            .map(|s| Duration::from_secs_f32(s).min(self.max_duration))
+            .filter(|end| *end > self.trim_in);
+        self.fade_tab_content[2].edited = false;
+        self.fade_tab_content[3].edited = false;
+    }
+
+    /// Fill the Trim In and Trim Out inputs with a suggestion the operator has to confirm
+    pub fn suggest_trim(&mut self, trim_in: Duration, trim_out: Duration) {
+        for (input, value) in [(2, trim_in), (3, trim_out)] {
+            self.fade_tab_content[input].input = format!("{:.2}", value.as_secs_f32());
+            self.fade_tab_content[input].reset_cursor();
+            self.fade_tab_content[input].edited = true;
+        }
+    }
+
+    /// A suggested trim is waiting for confirmation
+    pub fn has_trim_suggestion(&self) -> bool {
+        self.fade_tab_content[2].edited || self.fade_tab_content[3].edited
+    }
+
+    /// A cue playing several files together, each layer gets a Level and an Offset input
+    pub fn layered(name: String, layers: Vec<Layer>, max_duration: Duration) -> Self {
+        let mut item = SoundItem::new(name, max_duration);
+        for layer in &layers {
+            item.fade_tab_content.push(Input {
+                input_field_title: format!("{} Level", layer.file),
+                input: format!("{:.2}", layer.level),
+                ..Default::default()
+            });
+            item.fade_tab_content.push(Input {
+                input_field_title: format!("{} Offset", layer.file),
+                input: format!("{}", layer.offset.as_secs_f32()),
+                ..Default::default()
+            });
+        }
+        item.layers = layers;
+        item
+    }
+
+    /// Apply the Level or Offset input at this index of `fade_tab_content` to its layer
+    pub fn update_layer_from_input(&mut self, input: usize) {
+        let Some(layer) = self.layer_of_input(input) else {
+            return;
+        };
+        let Ok(value) = self.fade_tab_content[input].input.trim().parse::<f32>() else {
+            return;
+        };
+        if (input - LAYER_INPUTS_START).is_multiple_of(2) {
+            self.layers[layer].level = value.clamp(0.0, 2.0);
+        } else if value >= 0.0 && value.is_finite() {
+            self.layers[layer].offset = Duration::from_secs_f32(value);
+        }
+        self.max_duration = self
+            .layers
+            .iter()
+            .map(|l| l.duration + l.offset)
+            .max()
+            .unwrap_or_default();
+    }
+
+    /// Layer edited by the Level or Offset input at this index of `fade_tab_content`
+    pub fn layer_of_input(&self, input: usize) -> Option<usize> {
+        input
+            .checked_sub(LAYER_INPUTS_START)
+            .map(|i| i / 2)
+            .filter(|layer| *layer < self.layers.len())
+    }
+
     pub fn next_fade_tab(&mut self) {
         self.edit_tab_selected = (self.edit_tab_selected + 1) % self.fade_tab_content.len();
         for i in &mut self.fade_tab_content {
@@ -434,13 +946,102 @@ impl SoundItem {
         self.fade_tab_content[self.edit_tab_selected].input_mode =
             !self.fade_tab_content[self.edit_tab_selected].input_mode
     }
+
+    /// Add or replace a marker with the same name
+    pub fn add_marker(&mut self, marker: Marker) -> Result<(), String> {
+        if marker.position > self.max_duration {
+            return Err(format!(
+                "{} is after the end of {}",
+                format_duration(marker.position),
+                self.name
+            ));
+        }
+        self.markers.retain(|m| m.name != marker.name);
+        self.markers.push(marker);
+        self.markers.sort_by_key(|m| m.position);
+        Ok(())
+    }
+
+    pub fn marker(&self, name: &str) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn selected_marker(&self) -> Option<&Marker> {
+        self.markers.get(self.selected_marker)
+    }
+
+    pub fn remove_selected_marker(&mut self) {
+        if self.selected_marker < self.markers.len() {
+            self.markers.remove(self.selected_marker);
+            self.selected_marker = self
+                .selected_marker
+                .min(self.markers.len().saturating_sub(1));
+        }
+    }
+
+    pub fn next_marker(&mut self) {
+        if !self.markers.is_empty() {
+            self.selected_marker = (self.selected_marker + 1) % self.markers.len();
+        }
+    }
+
+    pub fn previous_marker(&mut self) {
+        if !self.markers.is_empty() {
+            self.selected_marker =
+                (self.selected_marker + self.markers.len() - 1) % self.markers.len();
+        }
+    }
+
+    /// First marker after the position, leaving a small margin to step over the current one
+    pub fn marker_after(&self, position: Duration) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .find(|m| m.position > position + Duration::from_millis(500))
+    }
+
+    /// Last marker before the position, leaving a small margin to step over the current one
+    pub fn marker_before(&self, position: Duration) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .rev()
+            .find(|m| m.position + Duration::from_millis(500) < position)
+    }
 }
 
 pub enum MusicState {
+    /// Pause or resume depending on the state of the sink
     PlayResume,
+    Pause,
+    Resume,
     Remove,
+    /// Fade to silence then stop, used by the panic
+    FadeOut(Duration),
     VolumeChanged(f32),
     LocalVolumeChanged(f32),
+    BusVolumeChanged(f32),
+    /// Jump to a position from the start of the file
+    Seek(Duration),
+    /// Jump backward or forward by a number of seconds
+    SeekBy(f32),
+    /// Go back to the trim in
+    Restart,
+}
+
+pub enum PlaylistState {
+    /// Crossfade to the next track now
+    Skip,
+    Pause,
+    Resume,
+    /// Fade out at the end of the current track instead of starting the next one, toggles
+    FadeOutAtEnd,
+    Stop,
+    /// Stop with another fade than the crossfade, used by the panic
+    FadeOut(Duration),
+    VolumeChanged(f32),
+    /// Gain of every bus, indexed like `SoundList::buses`
+    BusVolumeChanged(Vec<f32>),
 }
 
 impl SoundList {
@@ -450,18 +1051,29 @@ impl SoundList {
             sound_files,
             state: ListState::default(),
             current_dir: dir.clone(),
+            cue_list: None,
             selected: false,
             currently_playing: String::new(),
             volume: 1.0,
             editingfades: false,
+            buses: Bus::default_buses(),
+            playhead: Arc::new(Mutex::new(Duration::ZERO)),
+            layer_selection: Vec::new(),
+            playlist: PlaylistSettings::default(),
+            playlist_now_playing: Arc::new(Mutex::new(String::new())),
+            status: String::new(),
+            silence_threshold: -50.0,
+            pause_fade: Duration::from_millis(30),
+            panic_fade: Duration::from_millis(500),
         }
     }
 
     pub fn toggle_fade_edition(&mut self) {
-        if self.sound_files[self.state.selected().unwrap()].fade_tab_content[0].input_mode {
-            return;
-        }
-        if self.sound_files[self.state.selected().unwrap()].fade_tab_content[1].input_mode {
+        if self.sound_files[self.state.selected().unwrap()]
+            .fade_tab_content
+            .iter()
+            .any(|i| i.input_mode)
+        {
             return;
         }
         self.editingfades = !self.editingfades
@@ -489,6 +1101,253 @@ impl SoundList {
             ))
         }
     }
+    /// Gain of a bus once mute and solo are applied
+    pub fn bus_gain(&self, bus: usize) -> f32 {
+        let any_solo = self.buses.iter().any(|b| b.solo);
+        match self.buses.get(bus) {
+            Some(b) if b.mute || (any_solo && !b.solo) => 0.0,
+            Some(b) => b.volume,
+            None => 1.0,
+        }
+    }
+
+    pub fn bus_name(&self, bus: usize) -> &str {
+        self.buses.get(bus).map(|b| b.name.as_str()).unwrap_or("-")
+    }
+
+    pub fn bus_index(&self, name: &str) -> Option<usize> {
+        self.buses
+            .iter()
+            .position(|b| b.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn modify_bus_volume(&mut self, bus: usize, new_volume: f32) -> Result<(), String> {
+        match self.buses.get_mut(bus) {
+            Some(b) => {
+                b.volume = new_volume.clamp(0.0, 2.0);
+                Ok(())
+            }
+            None => Err(format!("Bus [{}] does not exist", bus)),
+        }
+    }
+
+    /// Route the item to the next bus
+    pub fn cycle_bus(&mut self, index: usize) {
+        if self.buses.is_empty() {
+            return;
+        }
+        if let Some(si) = self.sound_files.get_mut(index) {
+            si.bus = (si.bus + 1) % self.buses.len();
+        }
+    }
+
+    /// Gain of every bus once mute and solo are applied
+    pub fn bus_gains(&self) -> Vec<f32> {
+        (0..self.buses.len()).map(|b| self.bus_gain(b)).collect()
+    }
+
+    /// Select the next bus as the playlist group, then go back to every item
+    pub fn cycle_playlist_group(&mut self) {
+        self.playlist.group = match self.playlist.group {
+            None if !self.buses.is_empty() => Some(0),
+            Some(bus) if bus + 1 < self.buses.len() => Some(bus + 1),
+            _ => None,
+        };
+    }
+
+    pub fn playlist_group_name(&self) -> &str {
+        match self.playlist.group {
+            Some(bus) => self.bus_name(bus),
+            None => "All",
+        }
+    }
+
+    /// Items played by the background playlist, in list order
+    pub fn playlist_tracks(&self) -> Vec<SoundItem> {
+        self.sound_files
+            .iter()
+            .filter(|si| self.playlist.group.is_none_or(|bus| si.bus == bus))
+            .cloned()
+            .collect()
+    }
+
+    /// Bus gain of the currently playing item, if any
+    pub fn playing_bus_gain(&self) -> Option<f32> {
+        self.sound_files
+            .iter()
+            .find(|si| !self.currently_playing.is_empty() && si.name == self.currently_playing)
+            .map(|si| self.bus_gain(si.bus))
+    }
+
+    /// Pick or unpick a sound file for the next layered cue
+    pub fn toggle_layer_selection(&mut self, index: usize) {
+        if self.sound_files[index].layers.is_empty() {
+            if let Some(pos) = self.layer_selection.iter().position(|i| *i == index) {
+                self.layer_selection.remove(pos);
+            } else {
+                self.layer_selection.push(index);
+            }
+        }
+    }
+
+    /// Group the picked sound files in a new layered cue at the end of the list
+    pub fn create_layered_cue(&mut self) -> Result<usize, String> {
+        if self.layer_selection.len() < 2 {
+            return Err("Pick at least two sounds to build a layered cue".to_owned());
+        }
+        let picked: Vec<&SoundItem> = self
+            .layer_selection
+            .iter()
+            .filter_map(|i| self.sound_files.get(*i))
+            .collect();
+        let layers: Vec<Layer> = picked
+            .iter()
+            .map(|si| Layer {
+                file: si.name.clone(),
+                level: 1.0,
+                offset: Duration::ZERO,
+                duration: si.max_duration,
+            })
+            .collect();
+        let max_duration = picked
+            .iter()
+            .map(|si| si.max_duration)
+            .max()
+            .unwrap_or_default();
+        let count = self
+            .sound_files
+            .iter()
+            .filter(|si| !si.layers.is_empty())
+            .count();
+        let name = format!(
+            "Layered {} : {}",
+            count + 1,
+            layers
+                .iter()
+                .map(|l| l.file.as_str())
+                .collect::<Vec<_>>()
+                .join(" + ")
+        );
+        self.sound_files
+            .push(SoundItem::layered(name, layers, max_duration));
+        self.layer_selection.clear();
+        Ok(self.sound_files.len() - 1)
+    }
+
+    /// Remove a layered cue, plain sound files stay in the list
+    pub fn remove_layered_cue(&mut self, index: usize) {
+        if self
+            .sound_files
+            .get(index)
+            .is_some_and(|si| !si.layers.is_empty())
+        {
+            self.toggle_status();
+            self.sound_files.remove(index);
+            self.layer_selection.clear();
+            if self.sound_files.is_empty() {
+                self.state.select(None);
+            } else {
+                self.state
+                    .select(Some(index.min(self.sound_files.len() - 1)));
+                self.toggle_status();
+            }
+        }
+    }
+
+    /// Elapsed and total duration of the currently playing item, if any
+    pub fn playing_position(&self) -> Option<(Duration, Duration)> {
+        let si = self
+            .sound_files
+            .iter()
+            .find(|si| !self.currently_playing.is_empty() && si.name == self.currently_playing)?;
+        let elapsed = self.playhead.lock().map(|p| *p).unwrap_or_default();
+        Some((elapsed, si.max_duration))
+    }
+
+    /// Find the silence at both ends of the item and suggest it as Trim In and Trim Out
+    pub fn suggest_trim(&mut self, index: usize) -> Result<String, String> {
+        let Some(item) = self.sound_files.get_mut(index) else {
+            return Err(format!("Index : [{}] is out of bound", index));
+        };
+        match audio::detect_sound(&self.current_dir, item, self.silence_threshold)? {
+            Some((trim_in, trim_out)) => {
+                item.suggest_trim(trim_in, trim_out);
+                Ok(format!(
+                    "Suggested trim for {} : {:.2}s to {:.2}s",
+                    item.name,
+                    trim_in.as_secs_f32(),
+                    trim_out.as_secs_f32()
+                ))
+            }
+            None => Err(format!(
+                "{} is silent under {} dB",
+                item.name, self.silence_threshold
+            )),
+        }
+    }
+
+    /// Suggest a trim for every item of the list
+    pub fn suggest_all_trims(&mut self) -> String {
+        let mut errors = Vec::new();
+        for index in 0..self.sound_files.len() {
+            if let Err(e) = self.suggest_trim(index) {
+                errors.push(e);
+            }
+        }
+        match errors.first() {
+            None => format!("Suggested trim for {} sounds", self.sound_files.len()),
+            Some(e) => format!(
+                "Suggested trim for {} sounds, {} failed : {}",
+                self.sound_files.len() - errors.len(),
+                errors.len(),
+                e
+            ),
+        }
+    }
+
+    /// Render the item to the Bounce folder of the sound directory, returns the written file
+    pub fn bounce_item(
+        &self,
+        index: usize,
+        format: bounce::BounceFormat,
+    ) -> Result<String, String> {
+        let Some(item) = self.sound_files.get(index) else {
+            return Err(format!("Index : [{}] is out of bound", index));
+        };
+        let path = bounce::bounce_path(&self.current_dir, item, format);
+        let length = bounce::bounce(&self.current_dir, item, &path, format)?;
+        Ok(format!(
+            "Bounced {} to {} ({})",
+            item.name,
+            path.to_string_lossy(),
+            format_duration(length)
+        ))
+    }
+
+    /// Play the items of the playlist group one after another until stopped
+    pub fn play_playlist(&mut self, receiver: Receiver<PlaylistState>) -> Result<(), String> {
+        let tracks = self.playlist_tracks();
+        if tracks.is_empty() {
+            return Err(format!(
+                "No sound in the {} group to play",
+                self.playlist_group_name()
+            ));
+        }
+        let playlist = audio::Playlist {
+            dir: self.current_dir.clone(),
+            tracks,
+            shuffle: self.playlist.shuffle,
+            crossfade: self.playlist.crossfade,
+            general_volume: self.volume,
+            bus_gains: self.bus_gains(),
+            now_playing: self.playlist_now_playing.clone(),
+            pause_fade: self.pause_fade,
+            declick: audio::DeclickHandle::new(self.pause_fade),
+        };
+        thread::spawn(move || playlist.run(receiver));
+        Ok(())
+    }
+
     pub fn play(
         &mut self,
         receiver: Receiver<MusicState>,
@@ -496,83 +1355,127 @@ impl SoundList {
         index: usize,
         fade_in: Option<Duration>,
         fade_out: Option<Duration>,
+        start_at: Option<Duration>,
     ) {
         // local index
         // Offset Volume on each song
         let local_volume = self.sound_files[index].local_volume;
         let general_volume = self.volume;
+        let bus_volume = self.bus_gain(self.sound_files[index].bus);
         self.currently_playing = self.sound_files[index].name.clone();
         let trim_in_duration = self.sound_files[index].trim_in;
+        let trim_out = self.sound_files[index].trim_out;
+        let pause_fade = self.pause_fade;
+        let start_duration = start_at.unwrap_or(trim_in_duration);
+        let playhead = Arc::clone(&self.playhead);
         let arc_self = Arc::new(Mutex::new(self.clone()));
         thread::spawn(move || {
             let soundlist = arc_self.lock().unwrap();
-            let (_stream, stream_handle) = OutputStream::try_default().unwrap();
+            let (_stream, stream_handle) = audio::open_output().unwrap();
             let sink = Sink::try_new(&stream_handle).unwrap();
-            let sound_name = soundlist.sound_files[index].name.clone();
-            let mut sound_path = PathBuf::from(soundlist.current_dir.clone());
-            sound_path.push(sound_name);
-
-            let file =
-                BufReader::new(File::open(std::fs::canonicalize(sound_path).unwrap()).unwrap());
-            let source = Decoder::new(file).unwrap();
-            match (fade_in, fade_out) {
-                (Some(fade_in), Some(fade_out)) => {
-                    sink.append(source.fade_in(fade_in).fade_out(fade_out))
-                }
-                (Some(fade_in), None) => {
-                    sink.append(source.fade_in(fade_in));
-                }
-                (None, Some(fade_out)) => {
-                    sink.append(source.fade_out(fade_out));
-                }
-                _ => {
-                    sink.append(source);
-                }
-            }
-            sink.try_seek(trim_in_duration).unwrap();
+            let source = audio::playback_source(
+                &soundlist.current_dir,
+                &soundlist.sound_files[index],
+                fade_in,
+                fade_out,
+            )
+            .unwrap();
+            let declick = audio::DeclickHandle::new(pause_fade);
+            sink.append(audio::Declick::new(source, &declick));
+            sink.try_seek(start_duration).unwrap();
             let mut gv: f32 = general_volume;
             let mut lv: f32 = local_volume;
+            let mut bv: f32 = bus_volume;
+            sink.set_volume(mixed_volume(gv, lv, bv));
+            // Set while the de-click fade runs before the sink is paused
+            let mut pausing_until: Option<Instant> = None;
+            // Set while fading out before the sink is stopped
+            let mut stopping_until: Option<Instant> = None;
             loop {
-                if gv + lv <= 0.0 {
-                    sink.set_volume(0.0);
-                } else {
-                    sink.set_volume(gv + lv);
-                }
-                for i in receiver.iter() {
-                    match i {
+                let timeout = pausing_until
+                    .into_iter()
+                    .chain(stopping_until)
+                    .min()
+                    .map(|until| until.saturating_duration_since(Instant::now()))
+                    .unwrap_or(Duration::MAX)
+                    .min(Duration::from_millis(50));
+                match receiver.recv_timeout(timeout) {
+                    Ok(i) => match i {
                         MusicState::Remove => {
                             sink.clear();
                             let _ = sender.send(sink.volume());
                             {}
                         }
-                        MusicState::PlayResume => {
+                        MusicState::FadeOut(fade) => {
                             if sink.is_paused() {
-                                sink.play();
+                                sink.clear();
                             } else {
-                                sink.pause();
+                                declick.fade_to(0.0, fade);
+                                stopping_until = Some(Instant::now() + fade);
+                            }
+                        }
+                        MusicState::PlayResume | MusicState::Pause | MusicState::Resume => {
+                            let paused = sink.is_paused() || pausing_until.is_some();
+                            let pause = match &i {
+                                MusicState::Pause => true,
+                                MusicState::Resume => false,
+                                _ => !paused,
+                            };
+                            if pause && !paused {
+                                declick.set(0.0);
+                                pausing_until = Some(Instant::now() + pause_fade);
+                            } else if !pause && paused {
+                                pausing_until = None;
+                                sink.play();
+                                declick.set(1.0);
                             }
                         }
                         MusicState::VolumeChanged(new_volume) => {
                             // aply volume
                             gv = new_volume;
-                            if gv + lv <= 0.0 {
-                                sink.set_volume(0.0);
-                            } else {
-                                sink.set_volume(gv + lv);
-                                if sender.send(sink.volume()).is_ok() {};
-                            }
+                            sink.set_volume(mixed_volume(gv, lv, bv));
+                            if sender.send(sink.volume()).is_ok() {};
                         }
                         MusicState::LocalVolumeChanged(new_local_volume) => {
                             lv = new_local_volume;
-                            if gv + lv <= 0.0 {
-                                sink.set_volume(0.0);
-                            } else {
-                                sink.set_volume(gv + lv);
-                                if sender.send(sink.volume()).is_ok() {};
-                            }
+                            sink.set_volume(mixed_volume(gv, lv, bv));
+                            if sender.send(sink.volume()).is_ok() {};
+                        }
+                        MusicState::BusVolumeChanged(new_bus_volume) => {
+                            bv = new_bus_volume;
+                            sink.set_volume(mixed_volume(gv, lv, bv));
+                            if sender.send(sink.volume()).is_ok() {};
                         }
+                        MusicState::Seek(position) => {
+                            let item = &soundlist.sound_files[index];
+                            let _ = sink.try_seek(item.seek_target(position));
+                        }
+                        MusicState::SeekBy(offset) => {
+                            let item = &soundlist.sound_files[index];
+                            let _ = sink.try_seek(item.seek_by(sink.get_pos(), offset));
+                        }
+                        MusicState::Restart => {
+                            let _ = sink.try_seek(trim_in_duration);
+                        }
+                    },
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        thread::sleep(Duration::from_millis(50))
                     }
                 }
+                if pausing_until.is_some_and(|until| Instant::now() >= until) {
+                    pausing_until = None;
+                    sink.pause();
+                }
+                if stopping_until.is_some_and(|until| Instant::now() >= until) {
+                    sink.clear();
+                }
+                if let Ok(mut position) = playhead.lock() {
+                    *position = sink.get_pos();
+                }
+                if trim_out.is_some_and(|end| sink.get_pos() >= end) {
+                    sink.stop();
+                }
                 if sink.empty() {
                     break;
                 }
@@ -584,7 +1487,20 @@ impl SoundList {
     fn get_list_items(&self) -> Vec<ListItem> {
         self.sound_files
             .iter()
-            .map(|si| {
+            .enumerate()
+            .map(|(index, si)| {
+                let layer_tag = if !si.layers.is_empty() {
+                    " [Layered]"
+                } else if self.layer_selection.contains(&index) {
+                    " [+]"
+                } else {
+                    ""
+                };
+                let trim_tag = if si.has_trim_suggestion() {
+                    " [Trim ?]"
+                } else {
+                    ""
+                };
                 // Check if local volume is not edited
                 ListItem::new(
                     if let 0.0 = format!("{:.2}", si.local_volume)
@@ -594,10 +1510,18 @@ impl SoundList {
                     {
                         Text::from(vec![
                             // Song Title
-                            Line::from(vec![Span::styled(
-                                si.name.clone(),
-                                Style::default().fg(Color::White),
-                            )])
+                            Line::from(vec![
+                                Span::styled(
+                                    si.title().to_owned(),
+                                    Style::default().fg(Color::White),
+                                ),
+                                Span::styled(
+                                    format!(" [{}]", self.bus_name(si.bus)),
+                                    Style::default().fg(Color::DarkGray),
+                                ),
+                                Span::styled(layer_tag, Style::default().fg(Color::Cyan)),
+                                Span::styled(trim_tag, Style::default().fg(Color::Cyan)),
+                            ])
                             .left_aligned()
                             .fg(Color::White),
                             // Fade Text
@@ -620,10 +1544,18 @@ impl SoundList {
                     } else {
                         Text::from(vec![
                             // Song Title
-                            Line::from(vec![Span::styled(
-                                si.name.clone(),
-                                Style::default().fg(Color::White),
-                            )])
+                            Line::from(vec![
+                                Span::styled(
+                                    si.title().to_owned(),
+                                    Style::default().fg(Color::White),
+                                ),
+                                Span::styled(
+                                    format!(" [{}]", self.bus_name(si.bus)),
+                                    Style::default().fg(Color::DarkGray),
+                                ),
+                                Span::styled(layer_tag, Style::default().fg(Color::Cyan)),
+                                Span::styled(trim_tag, Style::default().fg(Color::Cyan)),
+                            ])
                             .left_aligned(),
                             // Local Volume
                             Line::from(Span::styled(
@@ -711,34 +1643,15 @@ impl SoundList {
                     if let Some(extension) = path.extension() {
                         if extension == "mp3" || extension == "wav" {
                             if let Some(file_name) = path.file_name() {
-                                sound_files.push(SoundItem {
-                                    name: file_name.to_string_lossy().into_owned(),
-                                    selected: false,
-                                    local_volume: 0.0,
-                                    edit_tab_selected: 0,
-                                    fade_tab_content: vec![
-                                        Input {
-                                            input_field_title: "Fade In Time".to_owned(),
-                                            is_selected: true,
-                                            ..Default::default()
-                                        },
-                                        Input {
-                                            input_field_title: "Fade Out Time".to_owned(),
-                                            ..Default::default()
-                                        },
-                                        Input {
-                                            input_field_title: "Trim In".to_owned(),
-                                            ..Default::default()
-                                        },
-                                    ],
-                                    trim_in: Duration::from_secs(0),
-                                    max_duration: lofty::read_from_path(Path::new(
+                                sound_files.push(SoundItem::new(
+                                    file_name.to_string_lossy().into_owned(),
+                                    lofty::read_from_path(Path::new(
                                         format!("{}", entry.path().to_string_lossy()).as_str(),
                                     ))
                                     .unwrap()
                                     .properties()
                                     .duration(),
-                                });
+                                ));
                             }
                         }
                     }
@@ -751,6 +1664,53 @@ impl SoundList {
     pub fn update(&mut self) {
         self.sound_files = SoundList::get_sound_files_from_dir(self.current_dir.clone())
     }
+
+    /// Replace the items with a cue list CSV in the order of its rows, the files are next to the CSV.
+    /// Returns the rows left out with their error
+    pub fn load_cue_list(&mut self, path: &Path) -> Result<Vec<String>, String> {
+        let (sound_files, errors) = cuelist::import(path)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        self.current_dir = dir.to_string_lossy().into_owned();
+        self.cue_list = Some(path.to_path_buf());
+        self.sound_files = sound_files;
+        self.layer_selection.clear();
+        self.state.select(None);
+        Ok(errors)
+    }
+
+    /// Outcome of a cue list import for the status line
+    pub fn cue_list_status(&self, result: Result<Vec<String>, String>) -> String {
+        match result {
+            Ok(errors) if errors.is_empty() => {
+                format!("{} cues imported", self.sound_files.len())
+            }
+            Ok(errors) => format!(
+                "{} cues imported, {} rows left out : {}",
+                self.sound_files.len(),
+                errors.len(),
+                errors.join(" | ")
+            ),
+            Err(e) => e,
+        }
+    }
+}
+
+/// Format a duration as mm:ss
+pub fn format_duration(duration: Duration) -> String {
+    format!(
+        "{:02}:{:02}",
+        duration.as_secs() / 60,
+        duration.as_secs() % 60
+    )
+}
+
+/// General volume and local volume offset, scaled by the bus gain
+fn mixed_volume(general: f32, local: f32, bus: f32) -> f32 {
+    if general + local <= 0.0 {
+        0.0
+    } else {
+        (general + local) * bus
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -768,3 +1728,85 @@ impl OscInfoWidget {
         self.info = new_info
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn free_port() -> u16 {
+        UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn ping(port: u16, receiver: &Receiver<OscReceived>) -> bool {
+        let packet = rosc::encoder::encode(&OscPacket::Message(rosc::OscMessage {
+            addr: "/Utilscord/Sync".to_owned(),
+            args: Vec::new(),
+        }))
+        .unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.send_to(&packet, ("127.0.0.1", port)).unwrap();
+        receiver.recv_timeout(Duration::from_secs(1)).is_ok()
+    }
+
+    #[test]
+    fn listening_again_keeps_the_listener_until_the_new_one_binds() {
+        let port = free_port();
+        let mut ipinput = IPInput::new("OSC".to_owned());
+        ipinput.input = format!("127.0.0.1:{}", port);
+        let first = ipinput.listen().unwrap();
+        assert!(ping(port, &first));
+
+        let busy = UdpSocket::bind("127.0.0.1:0").unwrap();
+        ipinput.input = format!("127.0.0.1:{}, {}", port, busy.local_addr().unwrap());
+        assert!(ipinput.listen().is_err());
+        assert!(ping(port, &first));
+
+        ipinput.input = format!("127.0.0.1:{}", port);
+        let second = ipinput.listen().unwrap();
+        assert!(ping(port, &second));
+    }
+
+    #[test]
+    fn seeks_stay_between_the_trims() {
+        let mut item = SoundItem::new("storm.wav".to_owned(), Duration::from_secs(10));
+        item.trim_in = Duration::from_secs(2);
+        item.trim_out = Some(Duration::from_secs(8));
+        assert_eq!(
+            item.seek_by(Duration::from_secs(3), -5.0),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            item.seek_by(Duration::from_secs(7), 5.0),
+            Duration::from_secs(8)
+        );
+        assert_eq!(
+            item.seek_by(Duration::from_secs(4), 2.0),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            item.seek_target(Duration::from_secs(9)),
+            Duration::from_secs(8)
+        );
+        item.trim_out = None;
+        assert_eq!(
+            item.seek_by(Duration::from_secs(9), 5.0),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn multicast_address_is_parsed_and_joined() {
+        let port = free_port();
+        let addr = ListenAddress::parse(&format!("239.1.2.3:{}", port)).unwrap();
+        assert_eq!(addr, ListenAddress::Udp(([239, 1, 2, 3], port).into()));
+        let Ok(OscSocket::Udp(socket)) = addr.bind() else {
+            panic!("{} is not bound", addr);
+        };
+        assert_eq!(socket.local_addr().unwrap().port(), port);
+        assert!(ListenAddress::parse("tcp://239.1.2.3:9000").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use super::SoundItem;
+use lofty::file::AudioFile;
+use std::path::Path;
+use std::time::Duration;
+
+/// Columns read from a cue list, matched on the header without case
+const FILE: &str = "file";
+const DISPLAY_NAME: &str = "display name";
+const FADE_IN: &str = "fade in";
+const FADE_OUT: &str = "fade out";
+const TRIM_IN: &str = "trim in";
+const LEVEL: &str = "level";
+const NOTES: &str = "notes";
+
+/// Read a cue list CSV, files are relative to the CSV. Returns the items in the order of the rows
+/// and one error per row left out
+pub fn import(path: &Path) -> Result<(Vec<SoundItem>, Vec<String>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| format!("Cannot read cue list {} : {}", path.to_string_lossy(), e))?;
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Cannot read cue list {} : {}", path.to_string_lossy(), e))?
+        .iter()
+        .map(|h| h.to_lowercase().replace(['_', '-'], " "))
+        .collect();
+    if !headers.iter().any(|h| h == FILE) {
+        return Err(format!(
+            "Cue list {} has no {} column",
+            path.to_string_lossy(),
+            FILE
+        ));
+    };
+    let dir = path.parent().unwrap_or(Path::new("."));
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    // Row and file of every item
+    let mut rows: Vec<(u64, String)> = Vec::new();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(format!("Invalid row : {}", e));
+                continue;
+            }
+        };
+        let row = record.position().map(|p| p.line()).unwrap_or_default();
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        // Items are found back by file name, by the show file, the history and the OSC feedback
+        let file = record_cell(&headers, &record, FILE);
+        if let Some((first, _)) = rows.iter().find(|(_, name)| name == file) {
+            errors.push(format!(
+                "Row {} : {} is already in row {}",
+                row, file, first
+            ));
+            continue;
+        }
+        match item_from_row(dir, &headers, &record) {
+            Ok(item) => {
+                rows.push((row, item.name.clone()));
+                items.push(item)
+            }
+            Err(e) => errors.push(format!("Row {} : {}", row, e)),
+        }
+    }
+    Ok((items, errors))
+}
+
+/// Value of the column with this header, empty when the column or the cell is missing
+fn record_cell<'a>(headers: &[String], record: &'a csv::StringRecord, name: &str) -> &'a str {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .and_then(|i| record.get(i))
+        .unwrap_or_default()
+}
+
+fn item_from_row(
+    dir: &Path,
+    headers: &[String],
+    record: &csv::StringRecord,
+) -> Result<SoundItem, String> {
+    let cell = |name: &str| record_cell(headers, record, name);
+    let file = cell(FILE);
+    if file.is_empty() {
+        return Err("no file".to_owned());
+    }
+    let duration = lofty::read_from_path(dir.join(file))
+        .map_err(|e| format!("{} : {}", file, e))?
+        .properties()
+        .duration();
+    let mut item = SoundItem::new(file.to_owned(), duration);
+
+    let display_name = cell(DISPLAY_NAME);
+    if !display_name.is_empty() {
+        item.display_name = Some(display_name.to_owned());
+    }
+    for (name, input) in [(FADE_IN, 0), (FADE_OUT, 1), (TRIM_IN, 2)] {
+        if let Some(seconds) = parse_seconds(name, cell(name))? {
+            if seconds > duration {
+                return Err(format!("{} {} is longer than {}", name, cell(name), file));
+            }
+            // Fades are whole seconds
+            item.fade_tab_content[input].input = if input == 2 {
+                seconds.as_secs_f32().to_string()
+            } else {
+                seconds.as_secs_f32().round().to_string()
+            };
+        }
+    }
+    item.apply_trim_inputs();
+    let level = cell(LEVEL);
+    if !level.is_empty() {
+        item.local_volume = match level.parse::<f32>() {
+            Ok(level) if (-2.0..=2.0).contains(&level) => level,
+            _ => return Err(format!("level {} is not in range -2..=2", level)),
+        };
+    }
+    item.set_notes(cell(NOTES));
+    Ok(item)
+}
+
+/// Seconds like `12.5` or minutes and seconds like `01:30`, None when the cell is empty
+fn parse_seconds(name: &str, text: &str) -> Result<Option<Duration>, String> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let seconds = match text.split_once(':') {
+        Some((minutes, seconds)) => minutes
+            .parse::<u32>()
+            .ok()
+            .zip(seconds.parse::<f32>().ok())
+            .map(|(m, s)| m as f32 * 60.0 + s),
+        None => text.parse::<f32>().ok(),
+    };
+    match seconds {
+        Some(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+            Ok(Some(Duration::from_secs_f32(seconds)))
+        }
+        _ => Err(format!("{} {} is not a time", name, text)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn cue_list_rows_are_ordered_and_checked() {
+        let dir = std::env::temp_dir().join("utilscord_cue_list_test");
+        fs::create_dir_all(&dir).unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        for name in ["b.wav", "a.wav"] {
+            let mut writer = hound::WavWriter::create(dir.join(name), spec).unwrap();
+            for _ in 0..8000 * 10 {
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+        let csv = dir.join("cues.csv");
+        fs::write(
+            &csv,
+            "File,Display Name,Fade In,Fade Out,Trim In,Level,Notes\n\
+             b.wav,Preshow,2,3,00:01.5,-0.5,\"Go, on house open\"\n\
+             missing.wav,,,,,,\n\
+             a.wav,Storm,abc,,,,\n\
+             a.wav,,,,,9,\n\
+             a.wav,,,,,,\n\
+             b.wav,Reprise,,,,,\n",
+        )
+        .unwrap();
+
+        let (items, errors) = import(&csv).unwrap();
+        assert_eq!(
+            items.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(),
+            ["b.wav", "a.wav"]
+        );
+        assert_eq!(items[0].title(), "Preshow");
+        assert_eq!(
+            items[0].fades(),
+            (Some(Duration::from_secs(2)), Some(Duration::from_secs(3)))
+        );
+        assert_eq!(items[0].trim_in, Duration::from_millis(1500));
+        assert_eq!(items[0].local_volume, -0.5);
+        assert_eq!(items[0].notes(), "Go, on house open");
+        assert_eq!(items[1].title(), "a.wav");
+
+        assert_eq!(errors.len(), 4);
+        assert!(
+            errors[0].starts_with("Row 3 : missing.wav"),
+            "{}",
+            errors[0]
+        );
+        assert_eq!(errors[1], "Row 4 : fade in abc is not a time");
+        assert_eq!(errors[2], "Row 5 : level 9 is not in range -2..=2");
+        assert_eq!(errors[3], "Row 7 : b.wav is already in row 2");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
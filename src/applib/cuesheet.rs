@@ -0,0 +1,371 @@
+use super::component::{format_duration, Content, SoundList};
+use super::TabManager;
+use open_dmx::DMX_CHANNELS;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const COLUMNS: [&str; 10] = [
+    "#", "Name", "Duration", "Fade In", "Fade Out", "Trim", "Level", "Bus", "Notes", "OSC",
+];
+
+/// One sound item as printed on the cue sheet
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cue {
+    pub index: usize,
+    pub name: String,
+    pub duration: Duration,
+    pub fade_in: Option<Duration>,
+    pub fade_out: Option<Duration>,
+    pub trim_in: Duration,
+    pub trim_out: Option<Duration>,
+    /// Local volume offset
+    pub level: f32,
+    pub bus: String,
+    pub notes: String,
+    /// OSC address firing the cue
+    pub osc: String,
+}
+
+impl Cue {
+    /// One cue per sound item, fired by Play under the OSC namespace of the show
+    pub fn from_sound_list(soundlist: &SoundList, namespace: &str) -> Vec<Self> {
+        soundlist
+            .sound_files
+            .iter()
+            .enumerate()
+            .map(|(index, si)| {
+                let (fade_in, fade_out) = si.fades();
+                Cue {
+                    index,
+                    name: si.title().to_owned(),
+                    duration: si.max_duration,
+                    fade_in,
+                    fade_out,
+                    trim_in: si.trim_in,
+                    trim_out: si.trim_out,
+                    level: si.local_volume,
+                    bus: soundlist.bus_name(si.bus).to_owned(),
+                    notes: si.notes().to_owned(),
+                    osc: format!("/{}/Play/{}", namespace, index),
+                }
+            })
+            .collect()
+    }
+
+    fn row(&self) -> [String; 10] {
+        let fade = |fade: Option<Duration>| match fade {
+            Some(fade) => format!("{}s", fade.as_secs_f32()),
+            None => "-".to_owned(),
+        };
+        let trim = match (self.trim_in.is_zero(), self.trim_out) {
+            (true, None) => "-".to_owned(),
+            (_, trim_out) => format!(
+                "{} - {}",
+                format_duration(self.trim_in),
+                format_duration(trim_out.unwrap_or(self.duration))
+            ),
+        };
+        [
+            self.index.to_string(),
+            self.name.clone(),
+            format_duration(self.duration),
+            fade(self.fade_in),
+            fade(self.fade_out),
+            trim,
+            format!("{:+.2}", self.level),
+            self.bus.clone(),
+            self.notes.clone(),
+            self.osc.clone(),
+        ]
+    }
+}
+
+/// DMX levels stored under a name, channel and level
+#[derive(Clone, Debug, PartialEq)]
+pub struct Look {
+    pub name: String,
+    pub levels: Vec<(usize, u8)>,
+}
+
+/// The Sound Bank with the DMX patch and looks, ready to print
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueSheet {
+    pub title: String,
+    pub cues: Vec<Cue>,
+    /// Fader name and DMX channel
+    pub patch: Vec<(String, usize)>,
+    pub looks: Vec<Look>,
+}
+
+impl CueSheet {
+    pub fn from_tabs(tab_manager: &TabManager) -> Self {
+        let mut sheet = CueSheet {
+            title: match &tab_manager.show_path {
+                Some(path) => path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                None => "Cue Sheet".to_owned(),
+            },
+            cues: Vec::new(),
+            patch: Vec::new(),
+            looks: Vec::new(),
+        };
+        for tab in &tab_manager.tabs {
+            match &tab.content {
+                Content::MainMenu(soundlist, _input) => {
+                    sheet.cues = Cue::from_sound_list(soundlist, &tab_manager.osc_namespace)
+                }
+                Content::Osc(_) | Content::Monitor(_) => (),
+                Content::Dmx(f1, f2, f3, f4, adr, _serial, _dmx_status) => {
+                    let faders = [f1, f2, f3, f4];
+                    sheet.patch = faders
+                        .iter()
+                        .enumerate()
+                        .map(|(id, f)| {
+                            (f.title.clone(), adr.wrapping_add(id).clamp(1, DMX_CHANNELS))
+                        })
+                        .collect();
+                    sheet.looks.push(Look {
+                        name: "Current".to_owned(),
+                        levels: sheet
+                            .patch
+                            .iter()
+                            .zip(faders)
+                            .map(|((_, channel), f)| (*channel, f.value))
+                            .collect(),
+                    });
+                }
+            }
+        }
+        sheet.looks.push(Look {
+            name: "Panic safe look".to_owned(),
+            levels: tab_manager.dmx_handler.safe_look.clone(),
+        });
+        sheet
+    }
+
+    pub fn to_csv(&self) -> Result<String, String> {
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(Vec::new());
+        let error = |e: csv::Error| format!("Cannot write the CSV cue sheet : {}", e);
+        writer.write_record(COLUMNS).map_err(error)?;
+        for cue in &self.cues {
+            writer.write_record(cue.row()).map_err(error)?;
+        }
+        writer.write_record([""]).map_err(error)?;
+        writer
+            .write_record(["DMX Patch", "Channel"])
+            .map_err(error)?;
+        for (name, channel) in &self.patch {
+            writer
+                .write_record([name.clone(), channel.to_string()])
+                .map_err(error)?;
+        }
+        writer.write_record([""]).map_err(error)?;
+        writer
+            .write_record(["Look", "Channel", "Level"])
+            .map_err(error)?;
+        for look in &self.looks {
+            if look.levels.is_empty() {
+                writer
+                    .write_record([look.name.as_str(), "All", "0"])
+                    .map_err(error)?;
+            }
+            for (channel, level) in &look.levels {
+                writer
+                    .write_record([look.name.clone(), channel.to_string(), level.to_string()])
+                    .map_err(error)?;
+            }
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| format!("Cannot write the CSV cue sheet : {}", e))?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        let table = |header: &[&str], rows: Vec<Vec<String>>| {
+            let mut out = format!("| {} |\n", header.join(" | "));
+            out += &format!("|{}\n", " --- |".repeat(header.len()));
+            for row in rows {
+                let row: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                out += &format!("| {} |\n", row.join(" | "));
+            }
+            out
+        };
+        let mut out = format!("# {}\n\n", cell(&self.title));
+        out += &table(
+            &COLUMNS,
+            self.cues.iter().map(|cue| cue.row().to_vec()).collect(),
+        );
+        out += "\n## DMX Patch\n\n";
+        out += &table(
+            &["Fader", "Channel"],
+            self.patch
+                .iter()
+                .map(|(name, channel)| vec![name.clone(), channel.to_string()])
+                .collect(),
+        );
+        for look in &self.looks {
+            out += &format!("\n## Look : {}\n\n", cell(&look.name));
+            if look.levels.is_empty() {
+                out += "Blackout, every channel at 0\n";
+            } else {
+                out += &table(&["Channel", "Level"], look_rows(look));
+            }
+        }
+        out
+    }
+
+    /// A single file with its style, printable from any browser
+    pub fn to_html(&self) -> String {
+        let table = |header: &[&str], rows: Vec<Vec<String>>| {
+            let mut out = String::from("<table>\n<tr>");
+            for title in header {
+                out += &format!("<th>{}</th>", escape_html(title));
+            }
+            out += "</tr>\n";
+            for row in rows {
+                out += "<tr>";
+                for cell in row {
+                    out += &format!("<td>{}</td>", escape_html(&cell));
+                }
+                out += "</tr>\n";
+            }
+            out + "</table>\n"
+        };
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             table {{ border-collapse: collapse; width: 100%; margin-bottom: 2em; }}\n\
+             th, td {{ border: 1px solid #444; padding: 4px 8px; text-align: left; vertical-align: top; }}\n\
+             th {{ background: #ddd; }}\n\
+             @media print {{ body {{ margin: 0; }} tr {{ page-break-inside: avoid; }} }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            escape_html(&self.title),
+            escape_html(&self.title)
+        );
+        out += &table(
+            &COLUMNS,
+            self.cues.iter().map(|cue| cue.row().to_vec()).collect(),
+        );
+        out += "<h2>DMX Patch</h2>\n";
+        out += &table(
+            &["Fader", "Channel"],
+            self.patch
+                .iter()
+                .map(|(name, channel)| vec![name.clone(), channel.to_string()])
+                .collect(),
+        );
+        for look in &self.looks {
+            out += &format!("<h2>Look : {}</h2>\n", escape_html(&look.name));
+            if look.levels.is_empty() {
+                out += "<p>Blackout, every channel at 0</p>\n";
+            } else {
+                out += &table(&["Channel", "Level"], look_rows(look));
+            }
+        }
+        out + "</body>\n</html>\n"
+    }
+}
+
+fn look_rows(look: &Look) -> Vec<Vec<String>> {
+    look.levels
+        .iter()
+        .map(|(channel, level)| vec![channel.to_string(), level.to_string()])
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write cue_sheet.csv, cue_sheet.md and cue_sheet.html in `dir`
+pub fn export(tab_manager: &TabManager, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let sheet = CueSheet::from_tabs(tab_manager);
+    let mut written = Vec::new();
+    for (extension, text) in [
+        ("csv", sheet.to_csv()?),
+        ("md", sheet.to_markdown()),
+        ("html", sheet.to_html()),
+    ] {
+        let path = dir.join(format!("cue_sheet.{}", extension));
+        fs::write(&path, text)
+            .map_err(|e| format!("Cannot write {} : {}", path.to_string_lossy(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::component::SoundItem;
+    use super::*;
+
+    #[test]
+    fn cues_are_fired_under_the_namespace() {
+        let mut soundlist = SoundList::from_dir(String::new());
+        soundlist.sound_files = vec![
+            SoundItem::new("intro.wav".to_owned(), Duration::from_secs(90)),
+            SoundItem::new("storm.wav".to_owned(), Duration::from_secs(30)),
+        ];
+        let cues = Cue::from_sound_list(&soundlist, "Stage");
+        assert_eq!(cues[1].osc, "/Stage/Play/1");
+        assert_eq!(cues[1].name, "storm.wav");
+    }
+
+    #[test]
+    fn cue_sheet_formats() {
+        let sheet = CueSheet {
+            title: "Act <1>".to_owned(),
+            cues: vec![Cue {
+                index: 0,
+                name: "intro.wav".to_owned(),
+                duration: Duration::from_secs(90),
+                fade_in: Some(Duration::from_secs(2)),
+                fade_out: None,
+                trim_in: Duration::from_secs(5),
+                trim_out: None,
+                level: -0.25,
+                bus: "Music".to_owned(),
+                notes: "Go on blackout | house lights, \"slow\"".to_owned(),
+                osc: "/Utilscord/Play/0".to_owned(),
+            }],
+            patch: vec![("Dimmer".to_owned(), 17)],
+            looks: vec![
+                Look {
+                    name: "Current".to_owned(),
+                    levels: vec![(17, 255)],
+                },
+                Look {
+                    name: "Panic safe look".to_owned(),
+                    levels: Vec::new(),
+                },
+            ],
+        };
+        let csv = sheet.to_csv().unwrap();
+        assert!(csv.starts_with("#,Name,Duration,Fade In,Fade Out,Trim,Level,Bus,Notes,OSC\n"));
+        assert!(csv.contains(
+            "0,intro.wav,01:30,2s,-,00:05 - 01:30,-0.25,Music,\"Go on blackout | house lights, \"\"slow\"\"\",/Utilscord/Play/0\n"
+        ));
+        assert!(csv.contains("Dimmer,17\n"));
+        assert!(csv.contains("Panic safe look,All,0\n"));
+
+        let markdown = sheet.to_markdown();
+        assert!(markdown.contains("| Go on blackout \\| house lights, \"slow\" |"));
+        assert!(markdown.contains("| Dimmer | 17 |"));
+
+        let html = sheet.to_html();
+        assert!(html.contains("<title>Act &lt;1&gt;</title>"));
+        assert!(html.contains("<td>Go on blackout | house lights, &quot;slow&quot;</td>"));
+        assert!(html.contains("<p>Blackout, every channel at 0</p>"));
+    }
+}
@@ -0,0 +1,377 @@
+use super::component::Content;
+use super::osc_tcp::{self, TcpClients};
+use super::TabManager;
+use open_dmx::DMX_CHANNELS;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::{SocketAddr, UdpSocket};
+
+/// Every feedback message is under this method of the namespace
+const FEEDBACK: &str = "Feedback";
+
+/// What the controllers are told, compared from one loop to the next to send only the changes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct State {
+    /// OSC namespace the feedback is sent under
+    pub namespace: String,
+    /// Title of the playing sound item, empty when nothing plays
+    pub playing: String,
+    /// Tenths of a second, finer changes are not sent
+    pub elapsed: u32,
+    pub remaining: u32,
+    pub volume: f32,
+    /// Local volume of every sound item
+    pub local_volumes: Vec<f32>,
+    /// DMX channel and level of every fader
+    pub dmx: Vec<(usize, u8)>,
+    /// DMX error in the DMX tab, empty when it runs
+    pub dmx_error: String,
+}
+
+impl State {
+    pub fn take(tab_manager: &TabManager) -> Self {
+        let mut state = State {
+            namespace: tab_manager.osc_namespace.clone(),
+            ..State::default()
+        };
+        for tab in &tab_manager.tabs {
+            match &tab.content {
+                Content::MainMenu(soundlist, _input) => {
+                    if let Some((elapsed, duration)) = soundlist.playing_position() {
+                        state.playing = soundlist
+                            .sound_files
+                            .iter()
+                            .find(|si| si.name == soundlist.currently_playing)
+                            .map(|si| si.title().to_owned())
+                            .unwrap_or_default();
+                        state.elapsed = (elapsed.as_millis() / 100) as u32;
+                        state.remaining =
+                            (duration.saturating_sub(elapsed).as_millis() / 100) as u32;
+                    }
+                    state.volume = soundlist.volume;
+                    state.local_volumes = soundlist
+                        .sound_files
+                        .iter()
+                        .map(|si| si.local_volume)
+                        .collect();
+                }
+                Content::Osc(_) | Content::Monitor(_) => (),
+                Content::Dmx(f1, f2, f3, f4, adr, _serial, dmx_status) => {
+                    state.dmx = [f1, f2, f3, f4]
+                        .iter()
+                        .enumerate()
+                        .map(|(id, f)| (adr.wrapping_add(id).clamp(1, DMX_CHANNELS), f.value))
+                        .collect();
+                    if dmx_status.starts_with("Error") {
+                        state.dmx_error = dmx_status.clone();
+                    }
+                }
+            }
+        }
+        state
+    }
+
+    /// Messages for the values that differ from `before`, every value without `before`
+    pub fn messages(&self, before: Option<&State>) -> Vec<OscMessage> {
+        let message = |name: &str, args: Vec<OscType>| OscMessage {
+            addr: format!("/{}/{}/{}", self.namespace, FEEDBACK, name),
+            args,
+        };
+        let mut messages = Vec::new();
+        if before.is_none_or(|b| b.playing != self.playing) {
+            messages.push(message(
+                "Playing",
+                vec![OscType::String(self.playing.clone())],
+            ));
+        }
+        if before.is_none_or(|b| b.elapsed != self.elapsed) {
+            messages.push(message(
+                "Elapsed",
+                vec![OscType::Float(self.elapsed as f32 / 10.0)],
+            ));
+        }
+        if before.is_none_or(|b| b.remaining != self.remaining) {
+            messages.push(message(
+                "Remaining",
+                vec![OscType::Float(self.remaining as f32 / 10.0)],
+            ));
+        }
+        if before.is_none_or(|b| b.volume != self.volume) {
+            messages.push(message("Volume", vec![OscType::Float(self.volume)]));
+        }
+        // A new list sends every item again
+        let same_list = before.is_some_and(|b| b.local_volumes.len() == self.local_volumes.len());
+        for (index, volume) in self.local_volumes.iter().enumerate() {
+            if !same_list || before.is_some_and(|b| b.local_volumes[index] != *volume) {
+                messages.push(message(
+                    "LocalVolume",
+                    vec![OscType::Int(index as i32), OscType::Float(*volume)],
+                ));
+            }
+        }
+        for (fader, (channel, level)) in self.dmx.iter().enumerate() {
+            if before.is_none_or(|b| b.dmx.get(fader) != Some(&(*channel, *level))) {
+                messages.push(message(
+                    "DMX",
+                    vec![OscType::Int(*channel as i32), OscType::Int(*level as i32)],
+                ));
+            }
+        }
+        if !self.dmx_error.is_empty() && before.is_none_or(|b| b.dmx_error != self.dmx_error) {
+            messages.push(message(
+                "Error",
+                vec![OscType::String(self.dmx_error.clone())],
+            ));
+        }
+        messages
+    }
+}
+
+/// Sends the state changes to the OSC reply destination and the TCP clients, the errors and the
+/// Sync answers go back where the message came from
+#[derive(Debug, Default)]
+pub struct Feedback {
+    socket: Option<UdpSocket>,
+    /// State sent last, None before the first send
+    last: Option<State>,
+    /// Where the message being run came from, None for the scheduled ones
+    source: Option<SocketAddr>,
+    /// Errors waiting for the next send and the source of their message
+    errors: Vec<(Option<SocketAddr>, String)>,
+    /// Sources of the Sync requests, each gets every value
+    sync: Vec<Option<SocketAddr>>,
+    /// TCP clients at the last send, a new client gets every value
+    tcp_peers: Vec<SocketAddr>,
+}
+
+impl Feedback {
+    /// The errors and the Sync requests that follow are answered to `source`
+    pub fn reply_to(&mut self, source: Option<SocketAddr>) {
+        self.source = source;
+    }
+
+    pub fn source(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    pub fn error(&mut self, error: String) {
+        self.errors.push((self.source, error));
+    }
+
+    pub fn request_sync(&mut self) {
+        self.sync.push(self.source);
+    }
+
+    /// Send what changed since the last call to the target and over the connection of every TCP
+    /// client, everything to a new client or after a Sync request. A source that is not a TCP
+    /// client is answered at the target
+    pub fn send(
+        &mut self,
+        target: Option<SocketAddr>,
+        tcp_clients: &TcpClients,
+        state: State,
+    ) -> Result<(), String> {
+        let peers = osc_tcp::peers(tcp_clients);
+        let errors = std::mem::take(&mut self.errors);
+        let sync = std::mem::take(&mut self.sync);
+        let changes = state.messages(self.last.as_ref());
+        let everything = state.messages(None);
+        let error = |e: &String| OscMessage {
+            addr: format!("/{}/{}/Error", state.namespace, FEEDBACK),
+            args: vec![OscType::String(e.clone())],
+        };
+        for peer in &peers {
+            let mut messages = if !self.tcp_peers.contains(peer) || sync.contains(&Some(*peer)) {
+                everything.clone()
+            } else {
+                changes.clone()
+            };
+            messages.extend(
+                errors
+                    .iter()
+                    .filter(|(source, _)| *source == Some(*peer))
+                    .map(|(_, e)| error(e)),
+            );
+            osc_tcp::send(tcp_clients, *peer, &encode(messages)?);
+        }
+        let at_target = |source: &Option<SocketAddr>| source.is_none_or(|s| !peers.contains(&s));
+        let mut messages = if sync.iter().any(at_target) {
+            everything
+        } else {
+            changes
+        };
+        messages.extend(
+            errors
+                .iter()
+                .filter(|(source, _)| at_target(source))
+                .map(|(_, e)| error(e)),
+        );
+        self.tcp_peers = peers;
+        self.last = Some(state);
+        let Some(target) = target else {
+            return Ok(());
+        };
+        if messages.is_empty() {
+            return Ok(());
+        }
+        if self.socket.is_none() {
+            let local = if target.is_ipv6() {
+                "[::]:0"
+            } else {
+                "0.0.0.0:0"
+            };
+            self.socket = Some(
+                UdpSocket::bind(local)
+                    .map_err(|e| format!("Cannot open the OSC feedback socket : {}", e))?,
+            );
+        }
+        let socket = self.socket.as_ref().expect("socket opened above");
+        for bytes in encode(messages)? {
+            socket
+                .send_to(&bytes, target)
+                .map_err(|e| format!("Cannot send OSC feedback to {} : {}", target, e))?;
+        }
+        Ok(())
+    }
+}
+
+fn encode(messages: Vec<OscMessage>) -> Result<Vec<Vec<u8>>, String> {
+    messages
+        .into_iter()
+        .map(|message| {
+            rosc::encoder::encode(&OscPacket::Message(message))
+                .map_err(|e| format!("Cannot encode OSC feedback : {}", e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Read;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+
+    fn received(socket: &UdpSocket) -> Vec<OscMessage> {
+        let mut buf = [0u8; rosc::decoder::MTU];
+        let mut messages = Vec::new();
+        while let Ok(size) = socket.recv(&mut buf) {
+            if let Ok((_, OscPacket::Message(message))) = rosc::decoder::decode_udp(&buf[..size]) {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    fn received_over_tcp(stream: &mut TcpStream) -> Vec<OscMessage> {
+        let mut decoder = osc_tcp::SlipDecoder::default();
+        let mut buf = [0u8; rosc::decoder::MTU];
+        let mut messages = Vec::new();
+        while let Ok(size @ 1..) = stream.read(&mut buf) {
+            for frame in decoder.push(&buf[..size]) {
+                if let Ok((_, OscPacket::Message(message))) = rosc::decoder::decode_udp(&frame) {
+                    messages.push(message);
+                }
+            }
+        }
+        messages
+    }
+
+    fn state() -> State {
+        State {
+            namespace: "Utilscord".to_owned(),
+            playing: "Storm".to_owned(),
+            elapsed: 15,
+            remaining: 285,
+            volume: 1.0,
+            local_volumes: vec![0.0, -0.5],
+            dmx: vec![(1, 0), (2, 255)],
+            dmx_error: String::new(),
+        }
+    }
+
+    #[test]
+    fn changes_are_sent_and_sync_sends_everything() {
+        let controller = UdpSocket::bind("127.0.0.1:0").unwrap();
+        controller
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let target = Some(controller.local_addr().unwrap());
+        let mut state = state();
+        let mut feedback = Feedback::default();
+        let no_clients = TcpClients::default();
+        feedback.send(target, &no_clients, state.clone()).unwrap();
+        assert_eq!(received(&controller).len(), 8);
+
+        state.elapsed = 16;
+        state.remaining = 284;
+        state.dmx[0].1 = 128;
+        feedback.error("Invalid OSC path : Fly".to_owned());
+        feedback.send(target, &no_clients, state.clone()).unwrap();
+        let messages = received(&controller);
+        assert_eq!(
+            messages.iter().map(|m| m.addr.as_str()).collect::<Vec<_>>(),
+            [
+                "/Utilscord/Feedback/Elapsed",
+                "/Utilscord/Feedback/Remaining",
+                "/Utilscord/Feedback/DMX",
+                "/Utilscord/Feedback/Error",
+            ]
+        );
+        assert_eq!(messages[0].args, [OscType::Float(1.6)]);
+        assert_eq!(messages[2].args, [OscType::Int(1), OscType::Int(128)]);
+
+        feedback.send(target, &no_clients, state.clone()).unwrap();
+        assert!(received(&controller).is_empty());
+        feedback.request_sync();
+        feedback.send(target, &no_clients, state).unwrap();
+        assert_eq!(received(&controller).len(), 8);
+    }
+
+    #[test]
+    fn sync_and_errors_go_back_to_the_client_that_asked() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        let clients = TcpClients::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let server = osc_tcp::serve(listener, sender, Arc::clone(&clients), Arc::clone(&stop));
+        let mut first = TcpStream::connect(addr).unwrap();
+        let mut second = TcpStream::connect(addr).unwrap();
+        for stream in [&first, &second] {
+            stream
+                .set_read_timeout(Some(Duration::from_millis(200)))
+                .unwrap();
+        }
+        while osc_tcp::peers(&clients).len() < 2 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let controller = UdpSocket::bind("127.0.0.1:0").unwrap();
+        controller
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let target = Some(controller.local_addr().unwrap());
+
+        // Every new client gets every value
+        let mut feedback = Feedback::default();
+        feedback.send(target, &clients, state()).unwrap();
+        assert_eq!(received_over_tcp(&mut first).len(), 8);
+        assert_eq!(received_over_tcp(&mut second).len(), 8);
+        assert_eq!(received(&controller).len(), 8);
+
+        feedback.reply_to(Some(second.local_addr().unwrap()));
+        feedback.request_sync();
+        feedback.error("Invalid OSC path : Fly".to_owned());
+        feedback.reply_to(None);
+        feedback.send(target, &clients, state()).unwrap();
+        assert!(received_over_tcp(&mut first).is_empty());
+        let messages = received_over_tcp(&mut second);
+        assert_eq!(messages.len(), 9);
+        assert_eq!(messages[8].addr, "/Utilscord/Feedback/Error");
+        assert!(received(&controller).is_empty());
+
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,399 @@
+use super::component::{Content, Tab};
+use std::time::{Duration, Instant};
+
+/// Edits of the same values closer than this are one step, like the characters typed in a fade
+const COALESCE_WINDOW: Duration = Duration::from_secs(1);
+/// Oldest steps are dropped past this length
+const HISTORY_LENGTH: usize = 200;
+/// How long the description of the last step stays in the status line
+const MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
+/// Fade In | Fade Out | Trim In | Trim Out, the first inputs of a sound item
+const INPUT_NAMES: [&str; 4] = ["Fade In", "Fade Out", "Trim In", "Trim Out"];
+
+/// One value changed by an edit, sound items are found back by name
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    LocalVolume {
+        item: String,
+        before: f32,
+        after: f32,
+    },
+    Input {
+        item: String,
+        input: usize,
+        before: String,
+        after: String,
+    },
+    Fader {
+        fader: usize,
+        name: String,
+        before: u8,
+        after: u8,
+    },
+    Address {
+        before: usize,
+        after: usize,
+    },
+}
+
+impl Change {
+    /// Two changes with the same target can be merged
+    fn same_target(&self, other: &Change) -> bool {
+        match (self, other) {
+            (Change::LocalVolume { item: a, .. }, Change::LocalVolume { item: b, .. }) => a == b,
+            (
+                Change::Input {
+                    item: a, input: i, ..
+                },
+                Change::Input {
+                    item: b, input: j, ..
+                },
+            ) => a == b && i == j,
+            (Change::Fader { fader: a, .. }, Change::Fader { fader: b, .. }) => a == b,
+            (Change::Address { .. }, Change::Address { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Keep the value before `self` and take the value after `later`
+    fn merge(&mut self, later: &Change) {
+        match (self, later) {
+            (Change::LocalVolume { after, .. }, Change::LocalVolume { after: later, .. }) => {
+                *after = *later
+            }
+            (Change::Input { after, .. }, Change::Input { after: later, .. }) => {
+                *after = later.clone()
+            }
+            (Change::Fader { after, .. }, Change::Fader { after: later, .. }) => *after = *later,
+            (Change::Address { after, .. }, Change::Address { after: later, .. }) => {
+                *after = *later
+            }
+            _ => (),
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        match self {
+            Change::LocalVolume { before, after, .. } => before == after,
+            Change::Input { before, after, .. } => before == after,
+            Change::Fader { before, after, .. } => before == after,
+            Change::Address { before, after } => before == after,
+        }
+    }
+
+    /// The change going back to the value before
+    pub fn inverted(&self) -> Change {
+        let mut inverted = self.clone();
+        match &mut inverted {
+            Change::LocalVolume { before, after, .. } => std::mem::swap(before, after),
+            Change::Input { before, after, .. } => std::mem::swap(before, after),
+            Change::Fader { before, after, .. } => std::mem::swap(before, after),
+            Change::Address { before, after } => std::mem::swap(before, after),
+        }
+        inverted
+    }
+
+    pub fn describe(&self) -> String {
+        let text = |value: &str| {
+            if value.is_empty() {
+                "-".to_owned()
+            } else {
+                value.to_owned()
+            }
+        };
+        match self {
+            Change::LocalVolume {
+                item,
+                before,
+                after,
+            } => format!("Local volume of {} : {:.2} -> {:.2}", item, before, after),
+            Change::Input {
+                item,
+                input,
+                before,
+                after,
+            } => format!(
+                "{} of {} : {} -> {}",
+                INPUT_NAMES[*input],
+                item,
+                text(before),
+                text(after)
+            ),
+            Change::Fader {
+                name,
+                before,
+                after,
+                ..
+            } => format!("DMX {} : {} -> {}", name, before, after),
+            Change::Address { before, after } => format!("DMX address : {} -> {}", before, after),
+        }
+    }
+}
+
+/// Values covered by the history, taken before and after every event to find what it changed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// Name, local volume and the fade and trim inputs of every sound item
+    items: Vec<(String, f32, [String; 4])>,
+    /// Title and value of every DMX fader
+    faders: Vec<(String, u8)>,
+    address: usize,
+}
+
+impl Snapshot {
+    pub fn take(tabs: &[Tab]) -> Self {
+        let mut snapshot = Snapshot::default();
+        for tab in tabs {
+            match &tab.content {
+                Content::MainMenu(soundlist, _input) => {
+                    snapshot.items = soundlist
+                        .sound_files
+                        .iter()
+                        .map(|si| {
+                            (
+                                si.name.clone(),
+                                si.local_volume,
+                                std::array::from_fn(|i| si.fade_tab_content[i].input.clone()),
+                            )
+                        })
+                        .collect()
+                }
+                Content::Osc(_) | Content::Monitor(_) => (),
+                Content::Dmx(f1, f2, f3, f4, adr, _serial, _dmx_status) => {
+                    snapshot.faders = [f1, f2, f3, f4]
+                        .iter()
+                        .map(|f| (f.title.clone(), f.value))
+                        .collect();
+                    snapshot.address = **adr;
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Changes from `self` to `after`, items missing on one side are not edits
+    fn diff(&self, after: &Snapshot) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for (name, volume, inputs) in &self.items {
+            let Some((_, new_volume, new_inputs)) = after.items.iter().find(|i| &i.0 == name)
+            else {
+                continue;
+            };
+            if volume != new_volume {
+                changes.push(Change::LocalVolume {
+                    item: name.clone(),
+                    before: *volume,
+                    after: *new_volume,
+                });
+            }
+            for (input, (before, new)) in inputs.iter().zip(new_inputs).enumerate() {
+                if before != new {
+                    changes.push(Change::Input {
+                        item: name.clone(),
+                        input,
+                        before: before.clone(),
+                        after: new.clone(),
+                    });
+                }
+            }
+        }
+        for (fader, ((name, before), (_, new))) in self.faders.iter().zip(&after.faders).enumerate()
+        {
+            if before != new {
+                changes.push(Change::Fader {
+                    fader,
+                    name: name.clone(),
+                    before: *before,
+                    after: *new,
+                });
+            }
+        }
+        if self.address != after.address {
+            changes.push(Change::Address {
+                before: self.address,
+                after: after.address,
+            });
+        }
+        changes
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    changes: Vec<Change>,
+    at: Instant,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self.changes.len() {
+            0 => String::new(),
+            1 => self.changes[0].describe(),
+            n => format!("{} (+{} more)", self.changes[0].describe(), n - 1),
+        }
+    }
+
+    /// Same values edited again soon after, the two edits are one step
+    fn coalesces_with(&self, changes: &[Change]) -> bool {
+        self.at.elapsed() < COALESCE_WINDOW
+            && self.changes.len() == changes.len()
+            && self
+                .changes
+                .iter()
+                .zip(changes)
+                .all(|(a, b)| a.same_target(b))
+    }
+}
+
+/// Undo and redo stacks of the Sound Bank and DMX edits
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    message: Option<(String, Instant)>,
+}
+
+impl History {
+    /// Store what changed between the two snapshots as a step, nothing when no value changed
+    pub fn record(&mut self, before: &Snapshot, after: &Snapshot) {
+        if before == after {
+            return;
+        }
+        // A new directory or a loaded show replaces the list, it is not an edit
+        if before.items.len() != after.items.len()
+            || before
+                .items
+                .iter()
+                .zip(&after.items)
+                .any(|(a, b)| a.0 != b.0)
+        {
+            return;
+        }
+        let changes = before.diff(after);
+        if changes.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        match self.undo.last_mut() {
+            Some(step) if step.coalesces_with(&changes) => {
+                for (change, later) in step.changes.iter_mut().zip(&changes) {
+                    change.merge(later);
+                }
+                step.at = Instant::now();
+                if step.changes.iter().all(Change::is_noop) {
+                    self.undo.pop();
+                    self.message = None;
+                    return;
+                }
+            }
+            _ => {
+                self.undo.push(Step {
+                    changes,
+                    at: Instant::now(),
+                });
+                if self.undo.len() > HISTORY_LENGTH {
+                    self.undo.remove(0);
+                }
+            }
+        }
+        if let Some(step) = self.undo.last() {
+            self.message = Some((step.describe(), Instant::now()));
+        }
+    }
+
+    /// Changes to apply to go back one step
+    pub fn undo(&mut self) -> Option<Vec<Change>> {
+        let Some(step) = self.undo.pop() else {
+            self.message = Some(("Nothing to undo".to_owned(), Instant::now()));
+            return None;
+        };
+        self.message = Some((format!("Undo : {}", step.describe()), Instant::now()));
+        let changes = step.changes.iter().rev().map(Change::inverted).collect();
+        self.redo.push(step);
+        Some(changes)
+    }
+
+    /// Changes to apply to redo the last undone step
+    pub fn redo(&mut self) -> Option<Vec<Change>> {
+        let Some(mut step) = self.redo.pop() else {
+            self.message = Some(("Nothing to redo".to_owned(), Instant::now()));
+            return None;
+        };
+        self.message = Some((format!("Redo : {}", step.describe()), Instant::now()));
+        let changes = step.changes.clone();
+        // A redone step is not merged with the next edit
+        step.at = Instant::now()
+            .checked_sub(COALESCE_WINDOW)
+            .unwrap_or(step.at);
+        self.undo.push(step);
+        Some(changes)
+    }
+
+    /// Description of the last step, for a few seconds
+    pub fn message(&self) -> Option<&str> {
+        self.message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < MESSAGE_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(fade_in: &str, dimmer: u8) -> Snapshot {
+        Snapshot {
+            items: vec![(
+                "intro.wav".to_owned(),
+                0.0,
+                [
+                    fade_in.to_owned(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ],
+            )],
+            faders: vec![("Dimmer".to_owned(), dimmer)],
+            address: 1,
+        }
+    }
+
+    #[test]
+    fn typed_characters_are_one_step() {
+        let mut history = History::default();
+        history.record(&snapshot("", 0), &snapshot("2", 0));
+        history.record(&snapshot("2", 0), &snapshot("2.", 0));
+        history.record(&snapshot("2.", 0), &snapshot("2.5", 0));
+        history.record(&snapshot("2.5", 0), &snapshot("2.5", 255));
+        assert_eq!(history.message(), Some("DMX Dimmer : 0 -> 255"));
+
+        assert_eq!(
+            history.undo().unwrap(),
+            vec![Change::Fader {
+                fader: 0,
+                name: "Dimmer".to_owned(),
+                before: 255,
+                after: 0
+            }]
+        );
+        assert_eq!(
+            history.undo().unwrap(),
+            vec![Change::Input {
+                item: "intro.wav".to_owned(),
+                input: 0,
+                before: "2.5".to_owned(),
+                after: String::new()
+            }]
+        );
+        assert!(history.undo().is_none());
+        assert_eq!(history.message(), Some("Nothing to undo"));
+
+        let redone = history.redo().unwrap();
+        assert_eq!(redone[0].describe(), "Fade In of intro.wav : - -> 2.5");
+        // A new edit drops what was left to redo
+        history.record(&snapshot("2.5", 0), &snapshot("3", 0));
+        assert!(history.redo().is_none());
+    }
+}
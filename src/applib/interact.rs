@@ -1,11 +1,36 @@
 #[path = "components.rs"]
 pub mod component;
+#[path = "cuesheet.rs"]
+pub mod cuesheet;
+#[path = "feedback.rs"]
+pub mod feedback;
+#[path = "history.rs"]
+pub mod history;
+#[path = "keymap.rs"]
+pub mod keymap;
+#[path = "learn.rs"]
+pub mod learn;
+#[path = "monitor.rs"]
+pub mod monitor;
+#[path = "osc_address.rs"]
+pub mod osc_address;
+#[path = "osc_send.rs"]
+pub mod osc_send;
+#[path = "osc_tcp.rs"]
+pub mod osc_tcp;
+#[path = "schedule.rs"]
+pub mod schedule;
+#[path = "show.rs"]
+pub mod show;
+use component::bounce::BounceFormat;
 use component::DMXInput;
 use component::IPInput;
 use component::MusicState;
-use component::{Content, Input, SoundList, Tab};
+use component::PlaylistState;
+use component::{Content, Input, Marker, SoundList, Tab};
 use core::panic;
 use open_dmx::DMX_CHANNELS;
+use osc_address::Handler;
 use ratatui::crossterm::event::KeyEvent;
 use ratatui::crossterm::event::KeyEventKind;
 use ratatui::crossterm::event::MouseEvent;
@@ -14,12 +39,14 @@ use rosc::OscBundle;
 use rosc::OscMessage;
 use rosc::OscPacket;
 use rosc::OscType;
-use std::env;
 use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use std::vec;
 
+use crate::cli::{Cli, DmxBackend};
 use crate::dmx::DMXHandler;
 
 #[derive(Debug)]
@@ -28,8 +55,32 @@ pub struct TabManager {
     pub selected_tab: usize,
     pub sender: Option<Sender<MusicState>>,
     pub receiver: Option<Receiver<f32>>,
-    pub osc_receiver: Option<Receiver<OscPacket>>,
+    pub playlist_sender: Option<Sender<PlaylistState>>,
+    /// Every sound is held by Pause All
+    pub paused_all: bool,
+    /// Set by the panic until the operator clears it
+    pub panic: bool,
+    pub osc_receiver: Option<Receiver<component::OscReceived>>,
+    /// First part of every OSC address, from `--osc-namespace` or the show
+    pub osc_namespace: String,
+    /// Where OSC replies are sent, from `--osc-reply` or the show
+    pub osc_reply: Option<SocketAddr>,
+    /// State changes and errors sent to `osc_reply`
+    pub feedback: feedback::Feedback,
+    /// Messages of OSC bundles waiting for their timetag
+    pub schedule: schedule::Schedule,
     pub dmx_handler: DMXHandler,
+    /// Show file saved or loaded last, Ctrl+S writes there
+    pub show_path: Option<PathBuf>,
+    /// The file explorer is open to pick a show file
+    pub browsing_show: bool,
+    pub autosave: show::Autosave,
+    /// Undo and redo of the Sound Bank and DMX edits
+    pub history: history::History,
+    /// Keys of the named actions, read from the keymap file at startup
+    pub keymap: keymap::Keymap,
+    /// The help screen with the active keys is open
+    pub show_help: bool,
 }
 
 impl TabManager {
@@ -48,24 +99,567 @@ impl TabManager {
         self.selected_tab = (self.selected_tab + self.tabs.len() - 1) % self.tabs.len()
     }
 
-    pub fn osc_bundle_interaction(&mut self, _osc_bundle: OscBundle) {}
+    /// Hold the playing sound and the playlist with a de-click fade, for a show stop
+    pub fn pause_all(&mut self) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(MusicState::Pause);
+        }
+        if let Some(playlist_sender) = &self.playlist_sender {
+            let _ = playlist_sender.send(PlaylistState::Pause);
+        }
+        self.paused_all = true;
+    }
 
-    pub fn osc_message_interaction(&mut self, osc_message: OscMessage) -> Result<(), String> {
-        let osc_path: Vec<&str> = osc_message.addr.split("/").collect();
-        match osc_path[2] {
-            "LocalVolume" | "Volume" | "Stop" | "Play" => {
-                match self.osc_message_soundlist(&osc_message, &osc_path) {
-                    Ok(_) => return Ok(()),
-                    Err(e) => {
-                        if let Content::Osc(ipinput) = &mut self.tabs[1].content {
-                            ipinput.update_info(format!("Error : {e}",));
+    pub fn resume_all(&mut self) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(MusicState::Resume);
+        }
+        if let Some(playlist_sender) = &self.playlist_sender {
+            let _ = playlist_sender.send(PlaylistState::Resume);
+        }
+        self.paused_all = false;
+    }
+
+    pub fn toggle_pause_all(&mut self) {
+        if self.paused_all {
+            self.resume_all();
+        } else {
+            self.pause_all();
+        }
+    }
+
+    /// Emergency stop : fade out every sound, cancel the playlist and send the DMX safe look
+    pub fn panic(&mut self) -> Result<(), String> {
+        self.panic = true;
+        // Cues scheduled by OSC bundles would start again after the panic
+        self.schedule.cancel();
+        self.paused_all = false;
+        let fade = match &mut self.tabs[0].content {
+            Content::MainMenu(soundlist, _input) => {
+                soundlist.currently_playing.clear();
+                soundlist.panic_fade
+            }
+            _ => Duration::from_millis(500),
+        };
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(MusicState::FadeOut(fade));
+        }
+        self.receiver = None;
+        if let Some(playlist_sender) = self.playlist_sender.take() {
+            let _ = playlist_sender.send(PlaylistState::FadeOut(fade));
+        }
+        self.dmx_handler.panic(&mut self.tabs[2].content)
+    }
+
+    pub fn clear_panic(&mut self) {
+        self.panic = false;
+    }
+
+    /// Open the DMX serial port of the DMX tab again, the safe look is kept
+    pub fn reopen_dmx(&mut self) {
+        if !self.dmx_handler.enabled {
+            return;
+        }
+        let safe_look = std::mem::take(&mut self.dmx_handler.safe_look);
+        self.dmx_handler = match DMXHandler::open_dmx(&mut self.tabs[2].content) {
+            Ok(dmx_handler) => dmx_handler,
+            Err(empty_dmx_handler) => empty_dmx_handler,
+        };
+        self.dmx_handler.safe_look = safe_look;
+    }
+
+    /// Save to the last show file, or to show.toml in the sound directory
+    pub fn save_show(&mut self) -> Result<PathBuf, String> {
+        let path = match &self.show_path {
+            Some(path) => path.clone(),
+            None => match &self.tabs[0].content {
+                Content::MainMenu(soundlist, _input) if !soundlist.current_dir.is_empty() => {
+                    Path::new(&soundlist.current_dir).join("show.toml")
+                }
+                _ => PathBuf::from("show.toml"),
+            },
+        };
+        show::save(self, &path)?;
+        self.show_path = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Stop every sound and replace the state of the tabs with the show file
+    pub fn load_show(&mut self, path: &Path) -> Result<Vec<String>, String> {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(MusicState::Remove);
+        }
+        self.receiver = None;
+        if let Some(playlist_sender) = self.playlist_sender.take() {
+            let _ = playlist_sender.send(PlaylistState::Stop);
+        }
+        self.paused_all = false;
+        let warnings = show::load(self, path)?;
+        self.show_path = Some(path.to_path_buf());
+        Ok(warnings)
+    }
+
+    /// Send the state changes and the errors to the OSC reply destination
+    pub fn send_feedback(&mut self) {
+        let state = feedback::State::take(self);
+        let tcp_clients = match &self.tabs[1].content {
+            Content::Osc(ipinput) => ipinput.tcp_clients.clone(),
+            _ => osc_tcp::TcpClients::default(),
+        };
+        if let Err(e) = self.feedback.send(self.osc_reply, &tcp_clients, state) {
+            self.show_status(e);
+        }
+    }
+
+    /// Last message of the sound list status line
+    pub fn status(&self) -> &str {
+        match &self.tabs[0].content {
+            Content::MainMenu(soundlist, _input) => &soundlist.status,
+            _ => "",
+        }
+    }
+
+    /// Write the outcome of a save or a load in the sound list status
+    fn show_status(&mut self, status: String) {
+        if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+            soundlist.status = status;
+        }
+    }
+
+    pub fn save_show_with_status(&mut self) {
+        let status = match self.save_show() {
+            Ok(path) => format!("Show saved to {}", path.to_string_lossy()),
+            Err(e) => e,
+        };
+        self.show_status(status);
+    }
+
+    pub fn load_show_with_status(&mut self, path: &Path) {
+        let status = match self.load_show(path) {
+            Ok(warnings) if warnings.is_empty() => {
+                format!("Show loaded from {}", path.to_string_lossy())
+            }
+            Ok(warnings) => format!(
+                "Show loaded from {} : {}",
+                path.to_string_lossy(),
+                warnings.join(", ")
+            ),
+            Err(e) => e,
+        };
+        self.show_status(status);
+    }
+
+    /// Rewrite the recovery file when an edit is waiting or the interval is over
+    pub fn autosave(&mut self) {
+        if !self.autosave.is_due() {
+            return;
+        }
+        if let Err(e) = show::save_recovery(self, &self.autosave.path) {
+            self.show_status(e);
+        }
+        self.autosave.saved();
+    }
+
+    pub fn restore_recovery(&mut self) {
+        self.autosave.offer_restore = false;
+        let path = self.autosave.path.clone();
+        let status = match show::load_recovery(self, &path) {
+            Ok(warnings) if warnings.is_empty() => "Last session restored".to_owned(),
+            Ok(warnings) => format!("Last session restored : {}", warnings.join(", ")),
+            Err(e) => e,
+        };
+        self.show_status(status);
+        self.autosave.edited = true;
+    }
+
+    pub fn discard_recovery(&mut self) {
+        self.autosave.offer_restore = false;
+        let _ = fs::remove_file(&self.autosave.path);
+        self.autosave.edited = true;
+    }
+
+    /// Keep the recovery file of an unclean exit next to where it was without restoring it, the
+    /// autosave starts over. Returns where the file was moved
+    pub fn set_aside_recovery(&mut self) -> Result<PathBuf, String> {
+        let aside = self.autosave.path.with_extension("toml.bak");
+        fs::rename(&self.autosave.path, &aside).map_err(|e| {
+            format!(
+                "Cannot move recovery file {} : {}",
+                self.autosave.path.to_string_lossy(),
+                e
+            )
+        })?;
+        self.autosave.offer_restore = false;
+        self.autosave.edited = true;
+        Ok(aside)
+    }
+
+    /// Fade out every sound for a clean exit, returns once the fade is over
+    pub fn shutdown(&mut self) {
+        let fade = match &self.tabs[0].content {
+            Content::MainMenu(soundlist, _input) => soundlist.panic_fade,
+            _ => Duration::from_millis(500),
+        };
+        let mut fading = false;
+        if let Some(sender) = self.sender.take() {
+            fading |= sender.send(MusicState::FadeOut(fade)).is_ok();
+        }
+        self.receiver = None;
+        if let Some(playlist_sender) = self.playlist_sender.take() {
+            fading |= playlist_sender.send(PlaylistState::FadeOut(fade)).is_ok();
+        }
+        if fading {
+            std::thread::sleep(fade);
+        }
+        self.end_session();
+    }
+
+    /// Clean exit : the recovery file is only left behind by a crash
+    pub fn end_session(&mut self) {
+        if !self.autosave.offer_restore {
+            let _ = fs::remove_file(&self.autosave.path);
+        }
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(changes) = self.history.undo() {
+            self.apply_changes(&changes);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(changes) = self.history.redo() {
+            self.apply_changes(&changes);
+        }
+    }
+
+    /// Set the values after the changes, the playing sound and the DMX output follow
+    fn apply_changes(&mut self, changes: &[history::Change]) {
+        for change in changes {
+            match change {
+                history::Change::LocalVolume { item, after, .. } => {
+                    let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content else {
+                        continue;
+                    };
+                    let Some(index) = soundlist.sound_files.iter().position(|si| &si.name == item)
+                    else {
+                        continue;
+                    };
+                    let _ = soundlist.modify_local_volume(index, *after);
+                    if &soundlist.currently_playing == item {
+                        if let Some(sender) = &self.sender {
+                            let _ = sender.send(MusicState::LocalVolumeChanged(
+                                soundlist.get_local_volume_of_item_index(index),
+                            ));
+                        }
+                    }
+                }
+                history::Change::Input {
+                    item, input, after, ..
+                } => {
+                    let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content else {
+                        continue;
+                    };
+                    if let Some(si) = soundlist.sound_files.iter_mut().find(|si| &si.name == item) {
+                        si.fade_tab_content[*input].input = after.clone();
+                        si.fade_tab_content[*input].reset_cursor();
+                        si.apply_trim_inputs();
+                    }
+                }
+                history::Change::Fader { fader, after, .. } => {
+                    if let Content::Dmx(f1, f2, f3, f4, ..) = &mut self.tabs[2].content {
+                        if let Some(f) = [f1, f2, f3, f4].into_iter().nth(*fader) {
+                            f.value = *after;
                         }
-                        return Err(e);
                     }
                 }
+                history::Change::Address { after, .. } => {
+                    if let Content::Dmx(.., adr, _serial, _dmx_status) = &mut self.tabs[2].content {
+                        **adr = *after;
+                    }
+                }
+            }
+        }
+        self.dmx_handler.update_dmx(&mut self.tabs[2].content);
+        self.autosave.edited = true;
+    }
+
+    /// Show systems the cues send OSC to, resolved when the show loads
+    fn osc_outputs(&self) -> osc_send::Outputs {
+        match &self.tabs[1].content {
+            Content::Osc(ipinput) => ipinput.outputs.clone(),
+            _ => osc_send::Outputs::default(),
+        }
+    }
+
+    /// Which keys of the keymap apply to the selected tab
+    pub fn key_context(&self) -> keymap::Context {
+        match &self.tabs[self.selected_tab].content {
+            Content::MainMenu(soundlist, _input) if soundlist.editingfades => {
+                keymap::Context::SongProperties
+            }
+            Content::MainMenu(..) => keymap::Context::SoundBank,
+            Content::Osc(_) => keymap::Context::Osc,
+            Content::Dmx(..) => keymap::Context::Dmx,
+            Content::Monitor(_) => keymap::Context::Monitor,
+        }
+    }
+
+    /// Text is being typed, only the global keys are remapped
+    pub fn is_typing(&self) -> bool {
+        match &self.tabs[self.selected_tab].content {
+            Content::MainMenu(soundlist, input) => {
+                (input.is_selected && input.input_mode)
+                    || (soundlist.editingfades
+                        && soundlist.state.selected().is_some_and(|i| {
+                            soundlist.sound_files[i]
+                                .fade_tab_content
+                                .iter()
+                                .any(|f| f.input_mode)
+                        }))
+            }
+            Content::Osc(ipinput) => {
+                ipinput.edit_mode
+                    || ipinput.learn.picking.is_some()
+                    || ipinput.learn.editing.is_some()
+                    || ipinput.test_message.is_some()
+            }
+            Content::Dmx(..) => self.dmx_handler.dmx_connection_option.is_none(),
+            Content::Monitor(monitor) => monitor.filtering,
+        }
+    }
+
+    /// The key the tabs expect for the key pressed, None when it has no action anymore
+    pub fn translate_key(&self, key: KeyEvent) -> Option<KeyEvent> {
+        self.keymap
+            .translate(self.key_context(), self.is_typing(), key)
+    }
+
+    /// Write the cue sheet in every format next to the show file, or in the sound directory
+    pub fn export_cue_sheet(&mut self) {
+        let dir = match (&self.show_path, &self.tabs[0].content) {
+            (Some(path), _) => path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            (None, Content::MainMenu(soundlist, _input)) if !soundlist.current_dir.is_empty() => {
+                PathBuf::from(&soundlist.current_dir)
+            }
+            _ => PathBuf::from("."),
+        };
+        let status = match cuesheet::export(self, &dir) {
+            Ok(_) => format!(
+                "Cue sheet exported to {} (CSV, Markdown, HTML)",
+                dir.join("cue_sheet.*").to_string_lossy()
+            ),
+            Err(e) => e,
+        };
+        self.show_status(status);
+    }
+
+    /// Pick a show file in the file explorer, Enter loads it and Esc cancels
+    fn browse_show_keys(
+        &mut self,
+        key: KeyEvent,
+        file_manager: &mut ratatui_explorer::FileExplorer,
+    ) {
+        match key.code {
+            KeyCode::Left => file_manager.handle(ratatui_explorer::Input::Left).unwrap(),
+            KeyCode::Right => file_manager.handle(ratatui_explorer::Input::Right).unwrap(),
+            KeyCode::Up => file_manager.handle(ratatui_explorer::Input::Up).unwrap(),
+            KeyCode::Down => file_manager.handle(ratatui_explorer::Input::Down).unwrap(),
+            KeyCode::Esc => self.browsing_show = false,
+            KeyCode::Enter => {
+                let path = file_manager.current().path().clone();
+                if file_manager.current().is_dir() {
+                    file_manager.handle(ratatui_explorer::Input::Right).unwrap();
+                } else {
+                    self.browsing_show = false;
+                    self.load_show_with_status(&path);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Run the messages of the bundle that are due and queue the others, returns the log lines
+    pub fn osc_bundle_interaction(
+        &mut self,
+        osc_bundle: OscBundle,
+        from: Option<SocketAddr>,
+    ) -> Vec<String> {
+        let mut lines = vec![format!(
+            "OSC bundle of {} packets at {:?}",
+            osc_bundle.content.len(),
+            <(u32, u32)>::from(osc_bundle.timetag)
+        )];
+        for osc_message in self.schedule.unpack(osc_bundle, SystemTime::now()) {
+            lines.push(self.run_osc_message(osc_message, from));
+        }
+        lines
+    }
+
+    /// Run one message, its errors go to the OSC feedback and it is logged in the monitor. Returns
+    /// the log line
+    fn run_osc_message(&mut self, osc_message: OscMessage, from: Option<SocketAddr>) -> String {
+        let received = format!("OSC {} {:?}", osc_message.addr, osc_message.args);
+        let (time, address, args) = (
+            SystemTime::now(),
+            osc_message.addr.clone(),
+            osc_message.args.clone(),
+        );
+        self.feedback.reply_to(from);
+        let handled = self.osc_message_interaction(osc_message);
+        let line = match &handled {
+            Ok(_) => received,
+            Err(e) => {
+                self.feedback.error(e.clone());
+                format!("{} : {}", received, e)
+            }
+        };
+        if let Content::Monitor(monitor) = &mut self.tabs[3].content {
+            monitor.push(monitor::Entry {
+                time,
+                from,
+                address,
+                args,
+                handled,
+            });
+        }
+        line
+    }
+
+    /// Handle the next OSC packet received within `timeout` and the scheduled messages that are due,
+    /// returns what was done for the log
+    pub fn handle_osc(&mut self, timeout: Duration) -> Vec<String> {
+        let mut lines = Vec::new();
+        // Wake up in time for the next scheduled message
+        let timeout = match self.schedule.next(SystemTime::now()) {
+            Some(next) => next.min(timeout),
+            None => timeout,
+        };
+        let packet = match &self.osc_receiver {
+            Some(receiver) => receiver.recv_timeout(timeout).ok(),
+            None => None,
+        };
+        if let Some(component::OscReceived { from, packet }) = packet {
+            match packet {
+                OscPacket::Message(osc_message) => {
+                    lines.push(self.run_osc_message(osc_message, Some(from)))
+                }
+                OscPacket::Bundle(osc_bundle) => {
+                    lines.extend(self.osc_bundle_interaction(osc_bundle, Some(from)))
+                }
+            }
+        }
+        for osc_message in self.schedule.due(SystemTime::now()) {
+            let line = self.run_osc_message(osc_message, None);
+            lines.push(format!("Scheduled {}", line));
+        }
+        lines
+    }
+
+    /// Returns the methods that ran, or what was learned
+    pub fn osc_message_interaction(
+        &mut self,
+        osc_message: OscMessage,
+    ) -> Result<Vec<String>, String> {
+        // Learned addresses run any action, the others skip the snapshots when they cannot edit
+        let mapped = match &self.tabs[1].content {
+            Content::Osc(ipinput) => ipinput
+                .learn
+                .mappings
+                .iter()
+                .any(|m| m.address == osc_message.addr),
+            _ => false,
+        };
+        if !mapped && osc_address::leaves_history(&self.osc_namespace, &osc_message.addr) {
+            return self.osc_message_dispatch(osc_message);
+        }
+        let before = history::Snapshot::take(&self.tabs);
+        let result = self.osc_message_dispatch(osc_message);
+        let after = history::Snapshot::take(&self.tabs);
+        // Controllers send many messages that change nothing, only an edit is autosaved early
+        self.autosave.edited |= before != after;
+        self.history.record(&before, &after);
+        result
+    }
+
+    /// Learn the message when an action waits for it, run the actions mapped to its address or
+    /// the methods it matches. Returns the methods that ran
+    fn osc_message_dispatch(&mut self, osc_message: OscMessage) -> Result<Vec<String>, String> {
+        let mut mapped = None;
+        if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+            if let Some(mapping) = ipinput.learn.learn(&osc_message) {
+                let info = format!("Learned {}", mapping);
+                ipinput.update_info(info.clone());
+                return Ok(vec![info]);
             }
+            mapped = ipinput.learn.map(&self.osc_namespace, &osc_message);
+        }
+        let Some(mapped) = mapped else {
+            return self.osc_address_dispatch(osc_message);
+        };
+        let mut handled = Vec::new();
+        let mut errors = Vec::new();
+        for message in mapped {
+            match message.and_then(|m| self.osc_address_dispatch(m)) {
+                Ok(methods) => handled.extend(methods),
+                Err(e) => errors.push(e),
+            }
+        }
+        if errors.is_empty() {
+            Ok(handled)
+        } else {
+            Err(errors.join(" | "))
+        }
+    }
 
-            "DMXChan" => match self.osc_message_dmx(&osc_message, &osc_path) {
+    /// Run the message on every method its address pattern matches, a plain address runs as is.
+    /// Returns the methods that ran
+    fn osc_address_dispatch(&mut self, osc_message: OscMessage) -> Result<Vec<String>, String> {
+        if !osc_address::is_pattern(&osc_message.addr) {
+            return self
+                .osc_method(&osc_message)
+                .map(|()| vec![osc_message.addr]);
+        }
+        let methods = osc_address::matching(
+            &osc_message.addr,
+            osc_address::address_space(&self.osc_namespace, &self.tabs),
+        )?;
+        if methods.is_empty() {
+            return Err(format!("No OSC method matches {}", osc_message.addr));
+        }
+        let errors: Vec<String> = methods
+            .iter()
+            .filter_map(|addr| {
+                self.osc_method(&OscMessage {
+                    addr: addr.clone(),
+                    args: osc_message.args.clone(),
+                })
+                .err()
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(methods)
+        } else {
+            Err(errors.join(" | "))
+        }
+    }
+
+    fn osc_method(&mut self, osc_message: &OscMessage) -> Result<(), String> {
+        let osc_path: Vec<&str> = osc_message.addr.split("/").collect();
+        if osc_path.len() < 3 {
+            return Err(format!(
+                "Missing OSC path namespace or method, ex : /{}/Play/0 <= {}",
+                self.osc_namespace, osc_message.addr
+            ));
+        }
+        if osc_path[1] != self.osc_namespace {
+            return Err(format!(
+                "Unknown OSC namespace /{}, expected /{}",
+                osc_path[1], self.osc_namespace
+            ));
+        }
+        match osc_address::handler(osc_path[2]) {
+            Some(Handler::SoundList) => match self.osc_message_soundlist(osc_message, &osc_path) {
                 Ok(_) => return Ok(()),
                 Err(e) => {
                     if let Content::Osc(ipinput) = &mut self.tabs[1].content {
@@ -74,7 +668,34 @@ impl TabManager {
                     return Err(e);
                 }
             },
-            _ => {}
+
+            Some(Handler::Dmx) => match self.osc_message_dmx(osc_message, &osc_path) {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                        ipinput.update_info(format!("Error : {e}",));
+                    }
+                    return Err(e);
+                }
+            },
+            Some(Handler::Sync) => {
+                // A TCP client is answered on its connection
+                let from_client = match (&self.tabs[1].content, self.feedback.source()) {
+                    (Content::Osc(ipinput), Some(source)) => {
+                        osc_tcp::peers(&ipinput.tcp_clients).contains(&source)
+                    }
+                    _ => false,
+                };
+                if self.osc_reply.is_none() && !from_client {
+                    return Err(
+                        "No OSC reply destination, start with --osc-reply HOST:PORT or connect over TCP"
+                            .to_owned(),
+                    );
+                }
+                self.feedback.request_sync();
+                return Ok(());
+            }
+            None => {}
         }
         Err(format!("Invalid OSC path : {}", osc_path[2]))
     }
@@ -85,7 +706,13 @@ impl TabManager {
         osc_path: &[&str],
     ) -> Result<(), String> {
         if osc_path[2] == "DMXChan" {
-            match (osc_path[3], &osc_message.args[0]) {
+            let (Some(channel), Some(value)) = (osc_path.get(3), osc_message.args.first()) else {
+                return Err(format!(
+                    "Missing OSC path channel or value, ex : /{}/DMXChan/1 255",
+                    self.osc_namespace
+                ));
+            };
+            match (*channel, value) {
                 (chan_str, osc_type)
                     if chan_str
                         .parse::<usize>()
@@ -167,7 +794,7 @@ impl TabManager {
                 _ => {
                     return Err(format!(
                         "Invalid channel or value : {} <= This must be between 1 and 512 {:?}",
-                        osc_path[3], osc_message.args[0]
+                        channel, value
                     ))
                 }
             }
@@ -271,6 +898,9 @@ impl TabManager {
                 for arg in osc_message.args.clone() {
                     if let Some(v) = arg.clone().float() {
                         soundlist.volume = v;
+                        if let Some(playlist_sender) = &self.playlist_sender {
+                            let _ = playlist_sender.send(PlaylistState::VolumeChanged(v));
+                        }
                         if let Some(sender) = &mut self.sender {
                             let _ =
                                 sender.send(component::MusicState::VolumeChanged(soundlist.volume));
@@ -289,6 +919,254 @@ impl TabManager {
                 return Err("Cannot modify Volume if there is no Main Menu".to_owned());
             }
         }
+        if osc_path[2] == "Bus" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                let (Some(bus_name), Some(parameter)) = (osc_path.get(3), osc_path.get(4)) else {
+                    return Err(
+                        "Missing OSC path bus name or parameter, ex : /Bus/SFX/Volume".to_owned(),
+                    );
+                };
+                let Some(bus) = soundlist.bus_index(bus_name) else {
+                    return Err(format!("Bus {} does not exist", bus_name));
+                };
+                let value = osc_message.args.first().cloned();
+                match *parameter {
+                    "Volume" => {
+                        let Some(value) = value else {
+                            return Err("No Volume Value provided".to_owned());
+                        };
+                        let Some(new_volume) = value.clone().float() else {
+                            return Err(format!("{:?}, is not a float", value));
+                        };
+                        soundlist.modify_bus_volume(bus, new_volume)?;
+                    }
+                    "Mute" | "Solo" => {
+                        let flag = match value {
+                            Some(OscType::Bool(b)) => Some(b),
+                            Some(OscType::Int(i)) => Some(i != 0),
+                            Some(OscType::Float(f)) => Some(f != 0.0),
+                            None => None,
+                            Some(other) => return Err(format!("{:?}, is not a bool", other)),
+                        };
+                        let b = &mut soundlist.buses[bus];
+                        let state = if *parameter == "Mute" {
+                            &mut b.mute
+                        } else {
+                            &mut b.solo
+                        };
+                        // No argument toggles
+                        *state = flag.unwrap_or(!*state);
+                    }
+                    _ => return Err(format!("Invalid Bus parameter : {}", parameter)),
+                }
+                send_bus_gain(&self.sender, &self.playlist_sender, soundlist);
+                let b = &soundlist.buses[bus];
+                let info = format!(
+                    "Bus {} : Volume {:.2}{}{}",
+                    b.name,
+                    b.volume,
+                    if b.mute { " | Muted" } else { "" },
+                    if b.solo { " | Solo" } else { "" }
+                );
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(info);
+                }
+                return Ok(());
+            }
+        }
+        if osc_path[2] == "Seek" || osc_path[2] == "Restart" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                if soundlist.currently_playing.is_empty() {
+                    return Err("No Sound is playing".to_owned());
+                }
+                let state = if osc_path[2] == "Restart" {
+                    component::MusicState::Restart
+                } else {
+                    let Some(arg) = osc_message.args.first() else {
+                        return Err("No Position Value provided".to_owned());
+                    };
+                    let seconds = match arg {
+                        OscType::Float(f) => *f,
+                        OscType::Int(i) => *i as f32,
+                        other => return Err(format!("{:?}, is not a float", other)),
+                    };
+                    if seconds < 0.0 {
+                        return Err(format!("{} is not a valid position", seconds));
+                    }
+                    component::MusicState::Seek(Duration::from_secs_f32(seconds))
+                };
+                if let Some(sender) = &mut self.sender {
+                    let _ = sender.send(state);
+                }
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(match osc_message.args.first() {
+                        Some(arg) if osc_path[2] == "Seek" => format!("Seek to {:?}", arg),
+                        _ => "Restart from Trim In".to_string(),
+                    });
+                }
+                return Ok(());
+            }
+        }
+        if osc_path[2] == "Playlist" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                let Some(command) = osc_path.get(3) else {
+                    return Err(
+                        "Missing OSC path playlist command, ex : /Playlist/Start".to_owned()
+                    );
+                };
+                let info = match *command {
+                    "Start" | "Stop" => {
+                        // A volume update doubles as a check that the playlist thread is alive
+                        let running = self.playlist_sender.as_ref().is_some_and(|s| {
+                            s.send(PlaylistState::VolumeChanged(soundlist.volume))
+                                .is_ok()
+                        });
+                        if running == (*command == "Start") {
+                            format!(
+                                "Playlist already {}",
+                                if running { "playing" } else { "stopped" }
+                            )
+                        } else if toggle_playlist(&mut self.playlist_sender, soundlist)? {
+                            format!("Playlist started on {}", soundlist.playlist_group_name())
+                        } else {
+                            "Playlist stopped".to_owned()
+                        }
+                    }
+                    "Skip" | "FadeOutAtEnd" => {
+                        let Some(playlist_sender) = &self.playlist_sender else {
+                            return Err("The Playlist is not playing".to_owned());
+                        };
+                        let state = if *command == "Skip" {
+                            PlaylistState::Skip
+                        } else {
+                            PlaylistState::FadeOutAtEnd
+                        };
+                        if playlist_sender.send(state).is_err() {
+                            return Err("The Playlist is not playing".to_owned());
+                        }
+                        format!("Playlist {}", command)
+                    }
+                    _ => return Err(format!("Invalid Playlist command : {}", command)),
+                };
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(info);
+                }
+                return Ok(());
+            }
+        }
+        if osc_path[2] == "Panic" {
+            if osc_path.get(3) == Some(&"Clear") {
+                self.clear_panic();
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info("Panic cleared".to_string());
+                }
+                return Ok(());
+            }
+            if let (Some(arg), Content::MainMenu(soundlist, _input)) =
+                (osc_message.args.first(), &mut self.tabs[0].content)
+            {
+                let seconds = match arg {
+                    OscType::Float(f) => *f,
+                    OscType::Int(i) => *i as f32,
+                    other => return Err(format!("{:?}, is not a float", other)),
+                };
+                if !(0.0..=10.0).contains(&seconds) {
+                    return Err(format!("{} is not in range 0..=10", seconds));
+                }
+                soundlist.panic_fade = Duration::from_secs_f32(seconds);
+            }
+            // The sound is stopped even if the DMX cannot follow
+            let dmx = self.panic();
+            if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                ipinput.update_info(match &dmx {
+                    Ok(()) => "PANIC : every sound stopped, DMX safe look sent".to_owned(),
+                    Err(e) => format!("PANIC : every sound stopped, DMX failed : {e}"),
+                });
+            }
+            return dmx;
+        }
+        if osc_path[2] == "PauseAll" || osc_path[2] == "ResumeAll" {
+            if osc_path[2] == "PauseAll" {
+                self.pause_all();
+            } else {
+                self.resume_all();
+            }
+            if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                ipinput.update_info(if self.paused_all {
+                    "Every sound paused".to_owned()
+                } else {
+                    "Every sound resumed".to_owned()
+                });
+            }
+            return Ok(());
+        }
+        if osc_path[2] == "PauseFade" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                let seconds = match osc_message.args.first() {
+                    Some(OscType::Float(f)) => *f,
+                    Some(OscType::Int(i)) => *i as f32,
+                    Some(other) => return Err(format!("{:?}, is not a float", other)),
+                    None => return Err("No Fade Value provided".to_owned()),
+                };
+                if !(0.0..=5.0).contains(&seconds) {
+                    return Err(format!("{} is not in range 0..=5", seconds));
+                }
+                soundlist.pause_fade = Duration::from_secs_f32(seconds);
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(format!("Pause fade set to {seconds}s"));
+                }
+                return Ok(());
+            }
+        }
+        if osc_path[2] == "SuggestTrim" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                match osc_message.args.first() {
+                    None => {}
+                    Some(OscType::Float(db)) => soundlist.silence_threshold = db.clamp(-96.0, 0.0),
+                    Some(OscType::Int(db)) => {
+                        soundlist.silence_threshold = (*db as f32).clamp(-96.0, 0.0)
+                    }
+                    Some(other) => return Err(format!("{:?}, is not a float", other)),
+                }
+                let info = match osc_path.get(3) {
+                    Some(&"All") => soundlist.suggest_all_trims(),
+                    Some(index) => match index.parse::<usize>() {
+                        Ok(index) => soundlist.suggest_trim(index)?,
+                        Err(_) => return Err(format!("Cannot Convert {} to usize", index)),
+                    },
+                    None => return Err(
+                        "Missing OSC path index of item, ex : /SuggestTrim/0 | /SuggestTrim/All"
+                            .to_owned(),
+                    ),
+                };
+                soundlist.status = info.clone();
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(info);
+                }
+                return Ok(());
+            }
+        }
+        if osc_path[2] == "Bounce" {
+            if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
+                let Some(index) = osc_path.get(3).and_then(|i| i.parse::<usize>().ok()) else {
+                    return Err("Missing OSC path index of item, ex : /Bounce/0".to_owned());
+                };
+                let format = match osc_message.args.first() {
+                    None => BounceFormat::Wav,
+                    Some(OscType::String(f)) if f.eq_ignore_ascii_case("wav") => BounceFormat::Wav,
+                    Some(OscType::String(f)) if f.eq_ignore_ascii_case("flac") => {
+                        BounceFormat::Flac
+                    }
+                    Some(other) => return Err(format!("{:?}, is not wav or flac", other)),
+                };
+                let info = soundlist.bounce_item(index, format)?;
+                soundlist.status = info.clone();
+                if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                    ipinput.update_info(info);
+                }
+                return Ok(());
+            }
+        }
         if osc_path[2] == "Stop" {
             if let Content::MainMenu(soundlist, _input) = &mut self.tabs[0].content {
                 soundlist.currently_playing.clear();
@@ -306,10 +1184,16 @@ impl TabManager {
             }
         }
         if osc_path[2] == "Play" {
+            let outputs = self.osc_outputs();
             if let Content::MainMenu(soundlist, input) = &mut self.tabs[0].content {
                 if soundlist.sound_files.is_empty() {
                     return Err("No Sound Files in the Sound List".to_owned());
                 }
+                if osc_path.len() < 4 {
+                    return Err(
+                        "Missing OSC path index of item, ex : /Play/0 | /Play/Next".to_owned()
+                    );
+                }
 
                 let index = if osc_path[3] == "Next" {
                     if self.selected_tab != 0 {
@@ -371,35 +1255,45 @@ impl TabManager {
                         soundlist.current_dir
                     ));
                 }
-                if let Some(sender) = &mut self.sender {
-                    let _ = sender.send(component::MusicState::Remove);
-                    {};
+                // /Play/{index}/Marker/{name}
+                if let (Some(&"Marker"), Some(marker_name)) = (osc_path.get(4), osc_path.get(5)) {
+                    let Some(marker) = soundlist.sound_files[index].marker(marker_name).cloned()
+                    else {
+                        return Err(format!(
+                            "Marker {} not found in {}",
+                            marker_name, soundlist.sound_files[index].name
+                        ));
+                    };
+                    if soundlist.currently_playing == soundlist.sound_files[index].name {
+                        if let Some(sender) = &mut self.sender {
+                            let _ = sender.send(component::MusicState::Seek(marker.position));
+                        }
+                    } else {
+                        start_playback(
+                            &mut self.sender,
+                            &mut self.receiver,
+                            &outputs,
+                            soundlist,
+                            index,
+                            Some(marker.position),
+                        );
+                    }
+                    if let Content::Osc(ipinput) = &mut self.tabs[1].content {
+                        ipinput.update_info(format!(
+                            "Playing item {} from marker {}",
+                            index, marker.name
+                        ));
+                    }
+                    return Ok(());
                 }
-                soundlist.currently_playing.clear();
-                let (mts, wtr) = mpsc::channel();
-                let (wts, mtr) = mpsc::channel();
-                self.sender = Some(mts);
-                self.receiver = Some(mtr);
-                let fadein = soundlist.sound_files[index] //A CHANGER
-                    .fade_tab_content[0]
-                    .input
-                    .as_mut_str();
-                let fadein = fadein.trim().parse::<f32>().unwrap_or(0.0);
-                let fadeout = soundlist.sound_files[index] //A CHANGER
-                    .fade_tab_content[1]
-                    .input
-                    .as_mut_str();
-                let fadeout = fadeout.trim().parse::<f32>().unwrap_or(0.0);
-                let fade_in_duration = match fadein {
-                    x if x > 0.0 => Some(Duration::from_secs(fadein as u64)),
-                    _ => None,
-                };
-                let fade_out_duration = match fadeout {
-                    x if x > 0.0 => Some(Duration::from_secs(fadeout as u64)),
-                    _ => None,
-                };
-
-                soundlist.play(wtr, wts, index, fade_in_duration, fade_out_duration);
+                start_playback(
+                    &mut self.sender,
+                    &mut self.receiver,
+                    &outputs,
+                    soundlist,
+                    index,
+                    None,
+                );
             }
             return Ok(());
         }
@@ -415,7 +1309,10 @@ impl TabManager {
             ratatui::crossterm::event::Event::FocusGained => self.handle_event_focus_gained(),
             ratatui::crossterm::event::Event::FocusLost => self.handle_event_focus_lost(),
             ratatui::crossterm::event::Event::Key(key_event) => {
-                self.handle_keys_event(key_event, file_manager)
+                let before = history::Snapshot::take(&self.tabs);
+                self.handle_keys_event(key_event, file_manager);
+                self.history
+                    .record(&before, &history::Snapshot::take(&self.tabs));
             }
             ratatui::crossterm::event::Event::Mouse(mouse_event) => {
                 self.handle_mouse_event(mouse_event);
@@ -429,6 +1326,35 @@ impl TabManager {
         key: KeyEvent,
         file_manager: &mut ratatui_explorer::FileExplorer,
     ) {
+        if key.kind == KeyEventKind::Press {
+            self.autosave.edited = true;
+        }
+        if self.autosave.offer_restore {
+            if key.kind == KeyEventKind::Press {
+                match key.code {
+                    KeyCode::Char('y' | 'Y') => self.restore_recovery(),
+                    KeyCode::Char('n' | 'N') | KeyCode::Esc => self.discard_recovery(),
+                    _ => (),
+                }
+            }
+            return;
+        }
+        if self.browsing_show {
+            if key.kind == KeyEventKind::Press {
+                self.browse_show_keys(key, file_manager);
+            }
+            return;
+        }
+        if let Content::Osc(ipinput) = &self.tabs[self.selected_tab].content {
+            if !ipinput.focus && key.kind == KeyEventKind::Press {
+                let actions = osc_address::actions(&self.tabs);
+                if let Content::Osc(ipinput) = &mut self.tabs[self.selected_tab].content {
+                    learn_tab(ipinput, key.code, || actions);
+                }
+                return;
+            }
+        }
+        let outputs = self.osc_outputs();
         match &mut self.tabs[self.selected_tab].content {
             Content::MainMenu(sound_list, input) if key.kind == KeyEventKind::Press => {
                 if input.is_selected {
@@ -467,6 +1393,22 @@ impl TabManager {
                                         }
                                     }
                                 }
+                                KeyCode::Up if key.modifiers == KeyModifiers::CONTROL => {
+                                    let bus = sound_list.sound_files[index].bus;
+                                    if let Some(b) = sound_list.buses.get(bus) {
+                                        let _ = sound_list.modify_bus_volume(bus, b.volume + 0.01);
+                                    }
+                                    send_bus_gain(&self.sender, &self.playlist_sender, sound_list);
+                                    return;
+                                }
+                                KeyCode::Down if key.modifiers == KeyModifiers::CONTROL => {
+                                    let bus = sound_list.sound_files[index].bus;
+                                    if let Some(b) = sound_list.buses.get(bus) {
+                                        let _ = sound_list.modify_bus_volume(bus, b.volume - 0.01);
+                                    }
+                                    send_bus_gain(&self.sender, &self.playlist_sender, sound_list);
+                                    return;
+                                }
                                 KeyCode::Up | KeyCode::Char('k' | 'K') => {
                                     sound_list.previous_song();
                                     return;
@@ -503,37 +1445,13 @@ impl TabManager {
                                     return;
                                 }
                                 KeyCode::Enter if key.kind == KeyEventKind::Press => {
-                                    if let Some(sender) = &mut self.sender {
-                                        let _ = sender.send(component::MusicState::Remove);
-                                        {};
-                                    }
-                                    sound_list.currently_playing.clear();
-                                    let (mts, wtr) = mpsc::channel();
-                                    let (wts, mtr) = mpsc::channel();
-                                    self.sender = Some(mts);
-                                    self.receiver = Some(mtr);
-                                    let fadein = sound_list.sound_files[index].fade_tab_content[0]
-                                        .input
-                                        .as_mut_str();
-                                    let fadein = fadein.trim().parse::<f32>().unwrap_or(0.0);
-                                    let fadeout = sound_list.sound_files[index].fade_tab_content[1]
-                                        .input
-                                        .as_mut_str();
-                                    let fadeout = fadeout.trim().parse::<f32>().unwrap_or(0.0);
-                                    let fade_in_duration = match fadein {
-                                        x if x > 0.0 => Some(Duration::from_secs(fadein as u64)),
-                                        _ => None,
-                                    };
-                                    let fade_out_duration = match fadeout {
-                                        x if x > 0.0 => Some(Duration::from_secs(fadeout as u64)),
-                                        _ => None,
-                                    };
-                                    sound_list.play(
-                                        wtr,
-                                        wts,
+                                    start_playback(
+                                        &mut self.sender,
+                                        &mut self.receiver,
+                                        &outputs,
+                                        sound_list,
                                         index,
-                                        fade_in_duration,
-                                        fade_out_duration,
+                                        None,
                                     );
                                     return;
                                 }
@@ -554,42 +1472,224 @@ impl TabManager {
                                     }
                                 }
 
-                                KeyCode::Char(' ') => {
-                                    if let Some(sender) = &mut self.sender {
-                                        let _ =
-                                            sender.send(component::MusicState::PlayResume).is_ok();
-                                        return;
-                                    }
+                                KeyCode::Char(' ') => {
+                                    if let Some(sender) = &mut self.sender {
+                                        let _ =
+                                            sender.send(component::MusicState::PlayResume).is_ok();
+                                        return;
+                                    }
+                                }
+
+                                KeyCode::Char('+') => {
+                                    sound_list.volume += 0.01;
+                                    sound_list.volume = sound_list.volume.clamp(0.0, 2.0);
+                                    if let Some(playlist_sender) = &self.playlist_sender {
+                                        let _ = playlist_sender
+                                            .send(PlaylistState::VolumeChanged(sound_list.volume));
+                                    }
+                                    if let Some(sender) = &mut self.sender {
+                                        let _ = sender.send(component::MusicState::VolumeChanged(
+                                            sound_list.volume,
+                                        ));
+                                        return;
+                                    }
+                                }
+
+                                KeyCode::Char('-') => {
+                                    sound_list.volume -= 0.01;
+                                    sound_list.volume = sound_list.volume.clamp(0.0, 2.0);
+                                    if let Some(playlist_sender) = &self.playlist_sender {
+                                        let _ = playlist_sender
+                                            .send(PlaylistState::VolumeChanged(sound_list.volume));
+                                    }
+                                    if let Some(sender) = &mut self.sender {
+                                        if sender
+                                            .send(component::MusicState::VolumeChanged(
+                                                sound_list.volume,
+                                            ))
+                                            .is_ok()
+                                        {
+                                            return;
+                                        };
+                                    }
+                                }
+
+                                KeyCode::Char('f') => {
+                                    sound_list.toggle_fade_edition();
+                                    return;
+                                }
+
+                                KeyCode::Left | KeyCode::Right
+                                    if key.modifiers == KeyModifiers::CONTROL =>
+                                {
+                                    // Crossfade length of the background playlist
+                                    let crossfade = sound_list.playlist.crossfade.as_secs_f32()
+                                        + if key.code == KeyCode::Left { -1.0 } else { 1.0 };
+                                    sound_list.playlist.crossfade =
+                                        Duration::from_secs_f32(crossfade.clamp(0.0, 30.0));
+                                    return;
+                                }
+
+                                KeyCode::Left | KeyCode::Right => {
+                                    // Shift for a fine jump
+                                    let step = if key.modifiers == KeyModifiers::SHIFT {
+                                        1.0
+                                    } else {
+                                        5.0
+                                    };
+                                    if let Some(sender) = &mut self.sender {
+                                        let _ = sender.send(MusicState::SeekBy(
+                                            if key.code == KeyCode::Left {
+                                                -step
+                                            } else {
+                                                step
+                                            },
+                                        ));
+                                    }
+                                    return;
+                                }
+
+                                KeyCode::Char('r') => {
+                                    if let Some(sender) = &mut self.sender {
+                                        let _ = sender.send(MusicState::Restart);
+                                    }
+                                    return;
+                                }
+
+                                KeyCode::Char('[' | ']') => {
+                                    // Jump between the markers of the playing sound
+                                    let Some((position, _)) = sound_list.playing_position() else {
+                                        return;
+                                    };
+                                    let Some(playing) = sound_list
+                                        .sound_files
+                                        .iter()
+                                        .find(|si| si.name == sound_list.currently_playing)
+                                    else {
+                                        return;
+                                    };
+                                    let marker = if key.code == KeyCode::Char(']') {
+                                        playing.marker_after(position)
+                                    } else {
+                                        playing.marker_before(position)
+                                    };
+                                    if let (Some(marker), Some(sender)) = (marker, &mut self.sender)
+                                    {
+                                        let _ = sender.send(MusicState::Seek(marker.position));
+                                    }
+                                    return;
+                                }
+
+                                KeyCode::Char('P') => {
+                                    let _ = toggle_playlist(&mut self.playlist_sender, sound_list);
+                                    return;
+                                }
+
+                                KeyCode::Char('n') => {
+                                    if let Some(playlist_sender) = &self.playlist_sender {
+                                        let _ = playlist_sender.send(PlaylistState::Skip);
+                                    }
+                                    return;
+                                }
+
+                                KeyCode::Char('e') => {
+                                    if let Some(playlist_sender) = &self.playlist_sender {
+                                        let _ = playlist_sender.send(PlaylistState::FadeOutAtEnd);
+                                    }
+                                    return;
+                                }
+
+                                KeyCode::Char('o') => {
+                                    sound_list.playlist.shuffle = !sound_list.playlist.shuffle;
+                                    return;
+                                }
+
+                                KeyCode::Char('g') => {
+                                    sound_list.cycle_playlist_group();
+                                    return;
+                                }
+
+                                KeyCode::Char('a') => {
+                                    sound_list.status = match sound_list.suggest_trim(index) {
+                                        Ok(info) => info,
+                                        Err(e) => format!("Error : {e}"),
+                                    };
+                                    return;
+                                }
+
+                                KeyCode::Char('A') => {
+                                    sound_list.status = sound_list.suggest_all_trims();
+                                    return;
+                                }
+
+                                KeyCode::Char('<' | '>') => {
+                                    let step = if key.code == KeyCode::Char('<') {
+                                        -1.0
+                                    } else {
+                                        1.0
+                                    };
+                                    sound_list.silence_threshold =
+                                        (sound_list.silence_threshold + step).clamp(-96.0, 0.0);
+                                    sound_list.status = format!(
+                                        "Silence threshold : {} dB",
+                                        sound_list.silence_threshold
+                                    );
+                                    return;
+                                }
+
+                                KeyCode::Char('w' | 'W') => {
+                                    // Shift for FLAC
+                                    let format = if key.code == KeyCode::Char('W') {
+                                        BounceFormat::Flac
+                                    } else {
+                                        BounceFormat::Wav
+                                    };
+                                    sound_list.status = match sound_list.bounce_item(index, format)
+                                    {
+                                        Ok(info) => info,
+                                        Err(e) => format!("Error : {e}"),
+                                    };
+                                    return;
+                                }
+
+                                KeyCode::Char('l') => {
+                                    sound_list.toggle_layer_selection(index);
+                                    return;
                                 }
 
-                                KeyCode::Char('+') => {
-                                    sound_list.volume += 0.01;
-                                    sound_list.volume = sound_list.volume.clamp(0.0, 2.0);
-                                    if let Some(sender) = &mut self.sender {
-                                        let _ = sender.send(component::MusicState::VolumeChanged(
-                                            sound_list.volume,
-                                        ));
-                                        return;
+                                KeyCode::Char('L') => {
+                                    if let Ok(new_index) = sound_list.create_layered_cue() {
+                                        sound_list.select_song(new_index);
                                     }
+                                    return;
                                 }
 
-                                KeyCode::Char('-') => {
-                                    sound_list.volume -= 0.01;
-                                    sound_list.volume = sound_list.volume.clamp(0.0, 2.0);
-                                    if let Some(sender) = &mut self.sender {
-                                        if sender
-                                            .send(component::MusicState::VolumeChanged(
-                                                sound_list.volume,
-                                            ))
-                                            .is_ok()
-                                        {
-                                            return;
-                                        };
+                                KeyCode::Char('x') => {
+                                    sound_list.remove_layered_cue(index);
+                                    return;
+                                }
+
+                                KeyCode::Char('b') => {
+                                    sound_list.cycle_bus(index);
+                                    send_bus_gain(&self.sender, &self.playlist_sender, sound_list);
+                                    return;
+                                }
+
+                                KeyCode::Char('m') => {
+                                    let bus = sound_list.sound_files[index].bus;
+                                    if let Some(b) = sound_list.buses.get_mut(bus) {
+                                        b.mute = !b.mute;
                                     }
+                                    send_bus_gain(&self.sender, &self.playlist_sender, sound_list);
+                                    return;
                                 }
 
-                                KeyCode::Char('f') => {
-                                    sound_list.toggle_fade_edition();
+                                KeyCode::Char('s') => {
+                                    let bus = sound_list.sound_files[index].bus;
+                                    if let Some(b) = sound_list.buses.get_mut(bus) {
+                                        b.solo = !b.solo;
+                                    }
+                                    send_bus_gain(&self.sender, &self.playlist_sender, sound_list);
                                     return;
                                 }
 
@@ -598,46 +1698,13 @@ impl TabManager {
                                         let index = c.to_string().parse::<usize>().unwrap();
                                         sound_list.select_song(index);
                                         if key.modifiers == KeyModifiers::CONTROL {
-                                            if let Some(sender) = &mut self.sender {
-                                                let _ = sender.send(component::MusicState::Remove);
-                                                {};
-                                            }
-                                            sound_list.currently_playing.clear();
-                                            let (mts, wtr) = mpsc::channel();
-                                            let (wts, mtr) = mpsc::channel();
-                                            self.sender = Some(mts);
-                                            self.receiver = Some(mtr);
-                                            let fadein = sound_list.sound_files[index]
-                                                .fade_tab_content[0]
-                                                .input
-                                                .as_mut_str();
-                                            let fadein =
-                                                fadein.trim().parse::<f32>().unwrap_or(0.0);
-                                            let fadeout = sound_list.sound_files[index]
-                                                .fade_tab_content[1]
-                                                .input
-                                                .as_mut_str();
-                                            let fadeout =
-                                                fadeout.trim().parse::<f32>().unwrap_or(0.0);
-                                            let fade_in_duration = match fadein {
-                                                x if x > 0.0 => {
-                                                    Some(Duration::from_secs(fadein as u64))
-                                                }
-                                                _ => None,
-                                            };
-                                            let fade_out_duration = match fadeout {
-                                                x if x > 0.0 => {
-                                                    Some(Duration::from_secs(fadeout as u64))
-                                                }
-                                                _ => None,
-                                            };
-
-                                            sound_list.play(
-                                                wtr,
-                                                wts,
+                                            start_playback(
+                                                &mut self.sender,
+                                                &mut self.receiver,
+                                                &outputs,
+                                                sound_list,
                                                 index,
-                                                fade_in_duration,
-                                                fade_out_duration,
+                                                None,
                                             );
                                             return;
                                         }
@@ -657,22 +1724,34 @@ impl TabManager {
                         }
                     }
                     if sound_list.editingfades {
-                        fade_tab(sound_list, key.code, key.modifiers);
+                        fade_tab(
+                            sound_list,
+                            key.code,
+                            key.modifiers,
+                            &mut self.sender,
+                            &mut self.receiver,
+                            &outputs,
+                        );
                     }
                 }
             }
             Content::Osc(listening_ip_input) if key.kind == KeyEventKind::Press => match key.code {
                 KeyCode::Enter => {
                     if listening_ip_input.focus {
+                        // Editing or a wrong address keeps the current listener
                         if let Ok(rcv) = listening_ip_input.toggle_edit_mode() {
                             self.osc_receiver = Some(rcv);
-                        } else {
-                            self.osc_receiver = None;
                         }
                     }
                 }
                 KeyCode::Char(char) => {
-                    if !matches!(char, '0'..='9' | ':' | '.') {
+                    // IPv4, IPv6 in brackets, tcp:// or udp:// and the commas between addresses
+                    if !(char.is_ascii_hexdigit()
+                        || matches!(
+                            char,
+                            ':' | '.' | '[' | ']' | ',' | ' ' | '/' | 't' | 'p' | 'u'
+                        ))
+                    {
                         return;
                     }
                     if listening_ip_input.edit_mode {
@@ -757,6 +1836,10 @@ impl TabManager {
                                 .update_dmx(&mut self.tabs[self.selected_tab].content);
                         }
                     }
+                    KeyCode::Char('S') => {
+                        self.dmx_handler
+                            .store_safe_look(&self.tabs[self.selected_tab].content);
+                    }
                     KeyCode::Char(char) => {
                         if self.dmx_handler.dmx_connection_option.is_some()
                             && key.modifiers == KeyModifiers::NONE
@@ -830,6 +1913,9 @@ impl TabManager {
                     _ => {}
                 }
             }
+            Content::Monitor(monitor) if key.kind == KeyEventKind::Press => {
+                monitor_tab(monitor, key.code)
+            }
             _ => {}
         }
     }
@@ -849,6 +1935,11 @@ impl TabManager {
                 }
             }
             Content::Dmx(_dmxinput, _dmxinput1, _dmxinput22, _dmxinput33, _, _, _) => (),
+            Content::Monitor(monitor) => {
+                if monitor.filtering {
+                    monitor.filter.push_str(&content);
+                }
+            }
         }
     }
     fn handle_event_resize(&mut self, _x: u16, _y: u16) {}
@@ -856,7 +1947,22 @@ impl TabManager {
 
 impl Default for TabManager {
     fn default() -> Self {
-        let args: Vec<_> = env::args().collect();
+        TabManager::new(&Cli::default())
+    }
+}
+
+impl TabManager {
+    /// Tabs set up from the command line, the show is loaded first and the other options override it
+    pub fn new(cli: &Cli) -> Self {
+        let sounds = cli
+            .sounds
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned());
+        // A .csv is a cue list, the sound files are next to it
+        let (sound_dir, cue_list) = match sounds {
+            Some(path) if path.ends_with(".csv") => (None, Some(path)),
+            sound_dir => (sound_dir, None),
+        };
         let mut dmx_content = Content::Dmx(
             DMXInput {
                 title: "Dimmer".to_owned(),
@@ -875,30 +1981,25 @@ impl Default for TabManager {
                 ..Default::default()
             },
             Box::new(1),
-            String::new(),
+            cli.dmx_port.clone().unwrap_or_default(),
             String::new(),
         );
-        let dmx_handler = match DMXHandler::open_dmx(&mut dmx_content) {
-            Ok(dmx_handler) => dmx_handler,
-            Err(empty_dmx_handler) => empty_dmx_handler,
+        let dmx_handler = match cli.dmx_backend {
+            DmxBackend::OpenDmx => match DMXHandler::open_dmx(&mut dmx_content) {
+                Ok(dmx_handler) => dmx_handler,
+                Err(empty_dmx_handler) => empty_dmx_handler,
+            },
+            DmxBackend::None => DMXHandler::disabled(&mut dmx_content),
         };
         let mut app = TabManager {
             tabs: vec![
                 Tab {
                     content: Content::MainMenu(
-                        SoundList::from_dir(if args.len() > 1 {
-                            args[1].clone()
-                        } else {
-                            String::new()
-                        }),
+                        SoundList::from_dir(sound_dir.clone().unwrap_or_default()),
                         Input {
                             input_field_title: "Path to Sound Files".to_owned(),
                             is_selected: true,
-                            input: if args.len() > 1 {
-                                args[1].clone()
-                            } else {
-                                String::new()
-                            },
+                            input: sound_dir.clone().unwrap_or_default(),
                             ..Default::default()
                         },
                     ),
@@ -909,17 +2010,75 @@ impl Default for TabManager {
                 Tab {
                     content: dmx_content,
                 },
+                Tab {
+                    content: Content::Monitor(monitor::Monitor::default()),
+                },
             ],
             selected_tab: 0,
             sender: None,
+            playlist_sender: None,
+            paused_all: false,
+            panic: false,
             receiver: None,
             osc_receiver: None,
+            osc_namespace: osc_address::DEFAULT_NAMESPACE.to_owned(),
+            osc_reply: None,
+            feedback: feedback::Feedback::default(),
+            schedule: schedule::Schedule::default(),
             dmx_handler,
+            show_path: None,
+            browsing_show: false,
+            autosave: show::Autosave::new(show::recovery_path()),
+            history: history::History::default(),
+            keymap: keymap::Keymap::load(&keymap::keymap_path()),
+            show_help: false,
         };
+        if let Some(error) = app.keymap.errors.first() {
+            app.show_status(format!(
+                "Keymap not loaded, default keys in use : {} (F1 for help)",
+                error
+            ));
+        }
         //CLI
-        if args.len() > 1 {
+        if sound_dir.is_some() {
+            app.tabs[0].next_content_element();
+        }
+        if let Some(path) = cue_list {
+            if let Content::MainMenu(soundlist, input) = &mut app.tabs[0].content {
+                let result = soundlist.load_cue_list(Path::new(&path));
+                soundlist.status = soundlist.cue_list_status(result);
+                input.input = path;
+            }
             app.tabs[0].next_content_element();
         }
+        if let Some(path) = &cli.show {
+            app.load_show_with_status(path);
+        }
+        if let Some(port) = &cli.dmx_port {
+            if let Content::Dmx(.., serial, _dmx_status) = &mut app.tabs[2].content {
+                if serial != port {
+                    *serial = port.clone();
+                    app.reopen_dmx();
+                }
+            }
+        }
+        if !cli.osc_listen.is_empty() {
+            if let Content::Osc(ipinput) = &mut app.tabs[1].content {
+                ipinput.input = cli
+                    .osc_listen
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                app.osc_receiver = ipinput.listen().ok();
+            }
+        }
+        if let Some(namespace) = &cli.osc_namespace {
+            app.osc_namespace = namespace.clone();
+        }
+        if cli.osc_reply.is_some() {
+            app.osc_reply = cli.osc_reply;
+        }
         app
     }
 }
@@ -940,8 +2099,16 @@ fn input_field_logic(
         match key {
             KeyCode::Enter => {
                 inputfield.toggle();
-                soundlist.current_dir =
-                    file_manager.current().path().to_string_lossy().into_owned();
+                let path = file_manager.current().path().clone();
+                if path.is_file() && path.extension().is_some_and(|e| e == "csv") {
+                    // Cue list
+                    let result = soundlist.load_cue_list(&path);
+                    soundlist.status = soundlist.cue_list_status(result);
+                    inputfield.input = path.to_string_lossy().into_owned();
+                    return;
+                }
+                soundlist.current_dir = path.to_string_lossy().into_owned();
+                soundlist.cue_list = None;
                 soundlist.update();
                 inputfield.input = soundlist.current_dir.clone();
             }
@@ -966,7 +2133,229 @@ fn input_field_logic(
     }
 }
 
-fn fade_tab(soundlist: &mut SoundList, key: KeyCode, keymod: KeyModifiers) {
+/// Stop the playing sound and play the item, from its trim in or from `start_at`. The OSC messages
+/// of the item are sent, the ones that could not be are in the status line
+fn start_playback(
+    sender: &mut Option<Sender<MusicState>>,
+    receiver: &mut Option<Receiver<f32>>,
+    outputs: &osc_send::Outputs,
+    soundlist: &mut SoundList,
+    index: usize,
+    start_at: Option<Duration>,
+) {
+    if let Some(sender) = sender {
+        let _ = sender.send(MusicState::Remove);
+    }
+    soundlist.currently_playing.clear();
+    let (mts, wtr) = mpsc::channel();
+    let (wts, mtr) = mpsc::channel();
+    *sender = Some(mts);
+    *receiver = Some(mtr);
+    let (fade_in_duration, fade_out_duration) = soundlist.sound_files[index].fades();
+    soundlist.play(
+        wtr,
+        wts,
+        index,
+        fade_in_duration,
+        fade_out_duration,
+        start_at,
+    );
+    // The sound plays even when a message is lost
+    if let Err(e) = osc_send::fire(outputs, &soundlist.sound_files[index].osc) {
+        soundlist.status = format!(
+            "OSC out of {} : {}",
+            soundlist.sound_files[index].title(),
+            e
+        );
+    }
+}
+
+/// Send the bus gain of the playing item after a bus fader, mute, solo or routing change
+fn send_bus_gain(
+    sender: &Option<Sender<MusicState>>,
+    playlist_sender: &Option<Sender<PlaylistState>>,
+    soundlist: &SoundList,
+) {
+    if let (Some(sender), Some(gain)) = (sender, soundlist.playing_bus_gain()) {
+        let _ = sender.send(MusicState::BusVolumeChanged(gain));
+    }
+    if let Some(playlist_sender) = playlist_sender {
+        let _ = playlist_sender.send(PlaylistState::BusVolumeChanged(soundlist.bus_gains()));
+    }
+}
+
+/// Stop the background playlist if it is running, start it otherwise
+fn toggle_playlist(
+    playlist_sender: &mut Option<Sender<PlaylistState>>,
+    soundlist: &mut SoundList,
+) -> Result<bool, String> {
+    if let Some(sender) = playlist_sender.take() {
+        // The send fails when the playlist already ended on its own
+        if sender.send(PlaylistState::Stop).is_ok() {
+            return Ok(false);
+        }
+    }
+    let (sender, receiver) = mpsc::channel();
+    soundlist.play_playlist(receiver)?;
+    *playlist_sender = Some(sender);
+    Ok(true)
+}
+
+/// Keys of the mapping list of the OSC tab, with the action picker, the mapping being edited and
+/// the message testing a destination. `actions` gives the address space when the picker opens
+fn learn_tab(ipinput: &mut IPInput, key: KeyCode, actions: impl FnOnce() -> Vec<String>) {
+    if let Some(text) = &mut ipinput.test_message {
+        match key {
+            KeyCode::Char(char) => text.push(char),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter => {
+                let sent = osc_send::CueMessage::parse(text).and_then(|message| {
+                    let to = message.send(&ipinput.outputs)?;
+                    Ok(format!("Sent {} to {}", message, to))
+                });
+                match sent {
+                    Ok(info) => {
+                        ipinput.test_message = None;
+                        ipinput.update_info(info);
+                    }
+                    Err(e) => ipinput.update_info(format!("Error : {e}")),
+                }
+            }
+            KeyCode::Esc => ipinput.test_message = None,
+            _ => (),
+        }
+        return;
+    }
+    let learn = &mut ipinput.learn;
+    if let Some(text) = &mut learn.editing {
+        match key {
+            KeyCode::Char(char) => text.push(char),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter => match learn.confirm_edit() {
+                Ok(()) => ipinput.update_info("Mapping saved"),
+                Err(e) => ipinput.update_info(format!("Error : {e}")),
+            },
+            KeyCode::Esc => learn.editing = None,
+            _ => (),
+        }
+        return;
+    }
+    if let Some(filter) = &mut learn.picking {
+        match key {
+            KeyCode::Char(char) => {
+                filter.push(char);
+                learn.picker_state.select(Some(0));
+            }
+            KeyCode::Backspace => {
+                filter.pop();
+                learn.picker_state.select(Some(0));
+            }
+            KeyCode::Up => learn.picker_state.select_previous(),
+            KeyCode::Down => learn.picker_state.select_next(),
+            KeyCode::Enter => {
+                let choices = learn.choices();
+                let picked = learn
+                    .picker_state
+                    .selected()
+                    .and_then(|i| choices.get(i.min(choices.len().saturating_sub(1))));
+                if let Some(action) = picked {
+                    let info = format!("Send the OSC message of the remote device for {}", action);
+                    learn.waiting = Some(action.to_string());
+                    learn.picking = None;
+                    ipinput.update_info(info);
+                }
+            }
+            KeyCode::Esc => learn.picking = None,
+            _ => (),
+        }
+        return;
+    }
+    match key {
+        KeyCode::Up => learn.state.select_previous(),
+        KeyCode::Down
+            if learn
+                .state
+                .selected()
+                .is_none_or(|i| i + 1 < learn.mappings.len()) =>
+        {
+            learn.state.select_next()
+        }
+        KeyCode::Char('l' | 'L') => learn.pick(actions()),
+        KeyCode::Char('t' | 'T') => match ipinput.destinations.first() {
+            Some(destination) => ipinput.test_message = Some(format!("{} ", destination.name)),
+            None => ipinput.update_info("No OSC destination, add them to the show"),
+        },
+        KeyCode::Enter => {
+            learn.editing = Some(
+                learn
+                    .state
+                    .selected()
+                    .and_then(|i| learn.mappings.get(i))
+                    .map(|m| m.to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        KeyCode::Delete => {
+            if let Some(mapping) = learn.delete_selected() {
+                ipinput.update_info(format!("Deleted {}", mapping));
+            }
+        }
+        KeyCode::Esc if learn.waiting.is_some() => {
+            learn.waiting = None;
+            ipinput.update_info("Learning cancelled");
+        }
+        _ => (),
+    }
+}
+
+/// Keys of the OSC monitor, the filter takes the typed characters until Enter or Esc
+fn monitor_tab(monitor: &mut monitor::Monitor, key: KeyCode) {
+    const PAGE: isize = 10;
+    if monitor.filtering {
+        match key {
+            KeyCode::Char(char) => monitor.filter.push(char),
+            KeyCode::Backspace => {
+                monitor.filter.pop();
+            }
+            KeyCode::Enter => monitor.filtering = false,
+            KeyCode::Esc => {
+                monitor.filter.clear();
+                monitor.filtering = false;
+            }
+            _ => (),
+        }
+        monitor.state.select(None);
+        return;
+    }
+    match key {
+        KeyCode::Char(' ') => monitor.toggle_pause(),
+        KeyCode::Char('/') => monitor.filtering = true,
+        KeyCode::Char('e' | 'E') => {
+            let _ = monitor.export(&show::home_dir());
+        }
+        KeyCode::Char('c' | 'C') => monitor.clear(),
+        KeyCode::Up | KeyCode::Char('k') => monitor.scroll(-1),
+        KeyCode::Down | KeyCode::Char('j') => monitor.scroll(1),
+        KeyCode::PageUp => monitor.scroll(-PAGE),
+        KeyCode::PageDown => monitor.scroll(PAGE),
+        KeyCode::Home => monitor.scroll(isize::MIN / 2),
+        KeyCode::End => monitor.state.select(None),
+        _ => (),
+    }
+}
+
+fn fade_tab(
+    soundlist: &mut SoundList,
+    key: KeyCode,
+    keymod: KeyModifiers,
+    sender: &mut Option<Sender<MusicState>>,
+    receiver: &mut Option<Receiver<f32>>,
+    outputs: &osc_send::Outputs,
+) {
     let si = &mut soundlist.sound_files[soundlist.state.selected().unwrap()];
 
     if si.fade_tab_content[0].input_mode {
@@ -1029,36 +2418,156 @@ fn fade_tab(soundlist: &mut SoundList, key: KeyCode, keymod: KeyModifiers) {
         }
     }
 
-    if si.fade_tab_content[2].input_mode {
-        // Editing Trim In
+    if let Some(editing) = (2..=3).find(|i| si.fade_tab_content[*i].input_mode) {
+        // Editing Trim In or Trim Out
         match key {
             KeyCode::Backspace => {
                 if keymod == KeyModifiers::CONTROL {
-                    si.fade_tab_content[2].input.clear();
-                    si.fade_tab_content[2].reset_cursor();
+                    si.fade_tab_content[editing].input.clear();
+                    si.fade_tab_content[editing].reset_cursor();
                 } else {
-                    si.fade_tab_content[2].delete_char();
+                    si.fade_tab_content[editing].delete_char();
+                }
+            }
+            KeyCode::Char(char_to_insert @ ('0'..='9' | '.')) => {
+                si.fade_tab_content[editing].enter_char(char_to_insert);
+                if si.fade_tab_content[editing]
+                    .input
+                    .parse::<f32>()
+                    .is_ok_and(|seconds| seconds > si.max_duration.as_secs_f32())
+                {
+                    si.fade_tab_content[editing].input = si.max_duration.as_secs().to_string();
                 }
             }
-            KeyCode::Char(char_to_insert) => {
-                if let '0'..='9' = char_to_insert {
-                    si.fade_tab_content[2].enter_char(char_to_insert);
+            _ => {}
+        }
+        si.apply_trim_inputs();
+    }
 
-                    match si.fade_tab_content[2].input.parse::<u64>() {
-                        Ok(duration) => {
-                            if si.max_duration.as_secs() > duration {
-                                si.trim_in = Duration::from_secs(duration);
-                            } else {
-                                si.fade_tab_content[2].input =
-                                    si.max_duration.as_secs().to_string();
-                                si.trim_in = Duration::from_secs(0);
-                            }
-                        }
-                        Err(_) => {
-                            si.fade_tab_content[2].enter_char(char_to_insert);
-                        }
+    if let Some(editing) = si
+        .fade_tab_content
+        .iter()
+        .position(|i| i.input_mode)
+        .filter(|i| si.layer_of_input(*i).is_some())
+    {
+        // Editing a Layer Level or Offset
+        match key {
+            KeyCode::Backspace => {
+                if keymod == KeyModifiers::CONTROL {
+                    si.fade_tab_content[editing].input.clear();
+                    si.fade_tab_content[editing].reset_cursor();
+                } else {
+                    si.fade_tab_content[editing].delete_char();
+                }
+            }
+            KeyCode::Char(char_to_insert @ ('0'..='9' | '.')) => {
+                si.fade_tab_content[editing].enter_char(char_to_insert)
+            }
+            KeyCode::Enter | KeyCode::Esc => si.edit(),
+            _ => {}
+        }
+        si.update_layer_from_input(editing);
+        return;
+    }
+
+    if si.fade_tab_content[4].input_mode {
+        // Editing a new Marker
+        match key {
+            KeyCode::Backspace => {
+                if keymod == KeyModifiers::CONTROL {
+                    si.fade_tab_content[4].input.clear();
+                    si.fade_tab_content[4].reset_cursor();
+                } else {
+                    si.fade_tab_content[4].delete_char();
+                }
+            }
+            KeyCode::Char(char_to_insert) => si.fade_tab_content[4].enter_char(char_to_insert),
+            KeyCode::Left => si.fade_tab_content[4].move_cursor_left(),
+            KeyCode::Right => si.fade_tab_content[4].move_cursor_right(),
+            KeyCode::Enter => {
+                let text = si.fade_tab_content[4].input.clone();
+                if !text.trim().is_empty() {
+                    match Marker::parse(&text).and_then(|marker| si.add_marker(marker)) {
+                        Ok(()) => si.fade_tab_content[4].input.clear(),
+                        Err(e) => si.fade_tab_content[4].input = e,
                     }
                 }
+                si.fade_tab_content[4].reset_cursor();
+                si.edit();
+            }
+            KeyCode::Esc => si.edit(),
+            _ => {}
+        }
+        return;
+    }
+
+    if si.fade_tab_content[5].input_mode {
+        // Editing the Notes
+        match key {
+            KeyCode::Backspace => {
+                if keymod == KeyModifiers::CONTROL {
+                    si.fade_tab_content[5].input.clear();
+                    si.fade_tab_content[5].reset_cursor();
+                } else {
+                    si.fade_tab_content[5].delete_char();
+                }
+            }
+            KeyCode::Char(char_to_insert) => si.fade_tab_content[5].enter_char(char_to_insert),
+            KeyCode::Left => si.fade_tab_content[5].move_cursor_left(),
+            KeyCode::Right => si.fade_tab_content[5].move_cursor_right(),
+            KeyCode::Enter | KeyCode::Esc => si.edit(),
+            _ => {}
+        }
+        return;
+    }
+
+    if si.fade_tab_content[6].input_mode {
+        // Editing the OSC messages sent when the cue fires
+        match key {
+            KeyCode::Backspace => {
+                if keymod == KeyModifiers::CONTROL {
+                    si.fade_tab_content[6].input.clear();
+                    si.fade_tab_content[6].reset_cursor();
+                } else {
+                    si.fade_tab_content[6].delete_char();
+                }
+            }
+            KeyCode::Char(char_to_insert) => si.fade_tab_content[6].enter_char(char_to_insert),
+            KeyCode::Left => si.fade_tab_content[6].move_cursor_left(),
+            KeyCode::Right => si.fade_tab_content[6].move_cursor_right(),
+            // The text stays to be fixed on an error
+            KeyCode::Enter if si.apply_osc_input().is_ok() => si.edit(),
+            KeyCode::Esc => {
+                si.set_osc(si.osc.clone());
+                si.edit();
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if si.fade_tab_content[4].is_selected {
+        // Marker list
+        match key {
+            KeyCode::Left => {
+                si.previous_marker();
+                return;
+            }
+            KeyCode::Right => {
+                si.next_marker();
+                return;
+            }
+            KeyCode::Delete => {
+                si.remove_selected_marker();
+                return;
+            }
+            KeyCode::Char('p') => {
+                if let Some(marker) = si.selected_marker().cloned() {
+                    let index = soundlist.state.selected().unwrap();
+                    let start_at = Some(marker.position);
+                    start_playback(sender, receiver, outputs, soundlist, index, start_at);
+                }
+                return;
             }
             _ => {}
         }
@@ -1071,6 +2580,14 @@ fn fade_tab(soundlist: &mut SoundList, key: KeyCode, keymod: KeyModifiers) {
                 soundlist.toggle_fade_edition();
             }
         }
+        KeyCode::Enter
+            if si.fade_tab_content[2..=3]
+                .iter()
+                .any(|i| i.is_selected && i.edited) =>
+        {
+            // Confirm the suggested trim
+            si.apply_trim_inputs();
+        }
         KeyCode::Up => {
             for i in si.fade_tab_content.clone() {
                 if i.input_mode {
@@ -1095,6 +2612,13 @@ fn fade_tab(soundlist: &mut SoundList, key: KeyCode, keymod: KeyModifiers) {
         KeyCode::Char('f') => {
             soundlist.toggle_fade_edition();
         }
+        KeyCode::Char('a') if !si.fade_tab_content.iter().any(|i| i.input_mode) => {
+            let index = soundlist.state.selected().unwrap();
+            soundlist.status = match soundlist.suggest_trim(index) {
+                Ok(info) => info,
+                Err(e) => format!("Error : {e}"),
+            };
+        }
         KeyCode::Esc => {
             soundlist.toggle_fade_edition();
         }
@@ -1121,10 +2645,66 @@ mod test {
         assert_eq!(res, Err(expected.to_string()))
     }
 
+    #[test]
+    fn short_address() {
+        test_osc(
+            "/Play",
+            None,
+            "Missing OSC path namespace or method, ex : /Utilscord/Play/0 <= /Play",
+        );
+        test_osc(
+            "/Utilscord/DMXChan",
+            Some(OscType::Int(255)),
+            "Missing OSC path channel or value, ex : /Utilscord/DMXChan/1 255",
+        );
+    }
+    #[test]
+    fn learned_mapping_runs_its_action() {
+        let mut t = TabManager::default();
+        let fader = OscMessage {
+            addr: "/desk/fader/8".to_owned(),
+            args: vec![OscType::Float(1.0)],
+        };
+        if let Content::Osc(ipinput) = &mut t.tabs[1].content {
+            ipinput.learn.waiting = Some("DMXChan/8".to_owned());
+        }
+        assert_eq!(
+            t.osc_message_interaction(fader.clone()),
+            Ok(vec![
+                "Learned /desk/fader/8 -> DMXChan/8 0..1 => 0..255".to_owned()
+            ])
+        );
+        assert_eq!(
+            t.osc_message_interaction(fader),
+            Err("No DMX connection found !".to_owned())
+        );
+    }
+    #[test]
+    fn wrong_namespace() {
+        test_osc(
+            "/OscControl/Stop",
+            None,
+            "Unknown OSC namespace /OscControl, expected /Utilscord",
+        );
+        test_osc("/OscControl/*", None, "No OSC method matches /OscControl/*");
+    }
+    #[test]
+    fn pattern_without_method() {
+        test_osc(
+            "/Utilscord/Fly/*",
+            None,
+            "No OSC method matches /Utilscord/Fly/*",
+        );
+        test_osc(
+            "/Utilscord/DMXChan/{8,9}",
+            Some(OscType::Int(255)),
+            "No DMX connection found ! | No DMX connection found !",
+        );
+    }
     #[test]
     fn no_dmx_connection() {
         test_osc(
-            "/OscControl/DMXChan/8",
+            "/Utilscord/DMXChan/8",
             Some(OscType::Int(255)),
             "No DMX connection found !",
         );
@@ -1132,7 +2712,7 @@ mod test {
     #[test]
     fn dmx_addr_overflow() {
         test_osc(
-            "/OscControl/DMXChan/513",
+            "/Utilscord/DMXChan/513",
             Some(OscType::Int(200)),
             "Invalid channel or value : 513 <= This must be between 1 and 512 Int(200)",
         );
@@ -1140,7 +2720,7 @@ mod test {
     #[test]
     fn dmx_addr_0() {
         test_osc(
-            "/OscControl/DMXChan/0",
+            "/Utilscord/DMXChan/0",
             Some(OscType::Int(200)),
             "Invalid channel or value : 0 <= This must be between 1 and 512 Int(200)",
         );
@@ -1148,7 +2728,7 @@ mod test {
     #[test]
     fn dmx_value_overflow() {
         test_osc(
-            "/OscControl/DMXChan/5",
+            "/Utilscord/DMXChan/5",
             Some(OscType::Int(256)),
             "256 is not in range 0..=255",
         );
@@ -1156,7 +2736,7 @@ mod test {
     #[test]
     fn dmx_value_wrong_type() {
         test_osc(
-            "/OscControl/DMXChan/5",
+            "/Utilscord/DMXChan/5",
             Some(OscType::Float(10.5)),
             "Float(10.5) is not an Int !",
         );
@@ -1164,19 +2744,19 @@ mod test {
     #[test]
     fn dmx_osc_path_verification() {
         test_osc(
-            "/OscControl/DMX/200",
+            "/Utilscord/DMX/200",
             Some(OscType::Int(10)),
             "Invalid OSC path : DMX",
         );
     }
     #[test]
     fn no_volume_value() {
-        test_osc("/OscControl/Volume", None, "No Volume Value provided");
+        test_osc("/Utilscord/Volume", None, "No Volume Value provided");
     }
     #[test]
     fn volume_wrong_type() {
         test_osc(
-            "/OscControl/Volume",
+            "/Utilscord/Volume",
             Some(OscType::Int(1)),
             "Int(1), is not a float",
         );
@@ -1184,7 +2764,7 @@ mod test {
     #[test]
     fn localvolume_no_value() {
         test_osc(
-            "/OscControl/LocalVolume/Selected",
+            "/Utilscord/LocalVolume/Selected",
             None,
             "Argument Value not provided",
         );
@@ -1192,7 +2772,7 @@ mod test {
     #[test]
     fn localvolume_missing_index() {
         test_osc(
-            "/OscControl/LocalVolume",
+            "/Utilscord/LocalVolume",
             Some(OscType::Float(10.0)),
             "Missing OSC path index of item, ex : Selected | 1 | 125 <= Sound Index",
         );
@@ -1201,9 +2781,60 @@ mod test {
     #[test]
     fn localvolume_wrong_type() {
         test_osc(
-            "/OscControl/LocalVolume/Selected",
+            "/Utilscord/LocalVolume/Selected",
             Some(OscType::String("Test".to_owned())),
             "Argument Value String(\"Test\") is not a Float",
         );
     }
+
+    #[test]
+    fn bus_not_found() {
+        test_osc(
+            "/Utilscord/Bus/Drums/Volume",
+            Some(OscType::Float(0.5)),
+            "Bus Drums does not exist",
+        );
+    }
+    #[test]
+    fn bus_missing_parameter() {
+        test_osc(
+            "/Utilscord/Bus/SFX",
+            Some(OscType::Float(0.5)),
+            "Missing OSC path bus name or parameter, ex : /Bus/SFX/Volume",
+        );
+    }
+    #[test]
+    fn seek_nothing_playing() {
+        test_osc(
+            "/Utilscord/Seek",
+            Some(OscType::Float(12.0)),
+            "No Sound is playing",
+        );
+    }
+    #[test]
+    fn panic_fade_out_of_range() {
+        test_osc(
+            "/Utilscord/Panic",
+            Some(OscType::Float(20.0)),
+            "20 is not in range 0..=10",
+        );
+    }
+    #[test]
+    fn marker_parse() {
+        assert_eq!(
+            Marker::parse("Chorus 2@63.5"),
+            Ok(Marker {
+                name: "Chorus 2".to_owned(),
+                position: Duration::from_secs_f32(63.5),
+            })
+        );
+        assert_eq!(
+            Marker::parse("Chorus"),
+            Err("Chorus is not formatted as name@seconds".to_owned())
+        );
+        assert_eq!(
+            Marker::parse("Chorus@-1"),
+            Err("-1 is not a valid position".to_owned())
+        );
+    }
 }
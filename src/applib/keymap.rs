@@ -0,0 +1,739 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the keys are handled. Global keys come first, Navigation only acts when the tab is not in use
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Context {
+    Global,
+    Navigation,
+    SoundBank,
+    SongProperties,
+    Osc,
+    Dmx,
+    Monitor,
+}
+
+impl Context {
+    const ALL: [Context; 7] = [
+        Context::Global,
+        Context::Navigation,
+        Context::SoundBank,
+        Context::SongProperties,
+        Context::Osc,
+        Context::Dmx,
+        Context::Monitor,
+    ];
+
+    /// Section of the keymap file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Navigation => "navigation",
+            Context::SoundBank => "sound_bank",
+            Context::SongProperties => "song_properties",
+            Context::Osc => "osc",
+            Context::Dmx => "dmx",
+            Context::Monitor => "monitor",
+        }
+    }
+}
+
+/// A named action and the keys hard-coded for it, the first one is what the tabs expect
+struct ActionInfo {
+    context: Context,
+    name: &'static str,
+    defaults: &'static [&'static str],
+    description: &'static str,
+}
+
+const fn action(
+    context: Context,
+    name: &'static str,
+    defaults: &'static [&'static str],
+    description: &'static str,
+) -> ActionInfo {
+    ActionInfo {
+        context,
+        name,
+        defaults,
+        description,
+    }
+}
+
+use Context::*;
+const ACTIONS: &[ActionInfo] = &[
+    action(Global, "quit", &["Ctrl+Q", "Ctrl+Esc"], "Quit"),
+    action(
+        Global,
+        "pause_all",
+        &["Ctrl+P"],
+        "Pause all / resume all sounds",
+    ),
+    action(Global, "panic", &["Ctrl+X"], "Panic"),
+    action(Global, "clear_panic", &["Ctrl+R"], "Clear the panic state"),
+    action(Global, "save_show", &["Ctrl+S"], "Save the show"),
+    action(Global, "open_show", &["Ctrl+O"], "Open a show file"),
+    action(
+        Global,
+        "export_cue_sheet",
+        &["Ctrl+E"],
+        "Export the cue sheet",
+    ),
+    action(Global, "undo", &["Ctrl+Z"], "Undo"),
+    action(Global, "redo", &["Ctrl+Y"], "Redo"),
+    action(Global, "help", &["F1"], "Show / hide this help"),
+    action(Navigation, "next_tab", &["Shift+Right"], "Next tab"),
+    action(Navigation, "previous_tab", &["Shift+Left"], "Previous tab"),
+    action(
+        Navigation,
+        "next_element",
+        &["Shift+Down", "Shift+J"],
+        "Next element",
+    ),
+    action(
+        Navigation,
+        "previous_element",
+        &["Shift+Up", "Shift+K"],
+        "Previous element",
+    ),
+    action(SoundBank, "play", &["Enter"], "Enter the list / play"),
+    action(SoundBank, "leave", &["Esc"], "Leave the list"),
+    action(SoundBank, "pause", &["Space"], "Pause"),
+    action(SoundBank, "stop", &["Backspace", "Delete"], "Stop"),
+    action(SoundBank, "up", &["Up", "K"], "Previous song"),
+    action(SoundBank, "down", &["Down", "J"], "Next song"),
+    action(
+        SoundBank,
+        "local_volume_up",
+        &["Shift+Up"],
+        "Local volume up",
+    ),
+    action(
+        SoundBank,
+        "local_volume_down",
+        &["Shift+Down"],
+        "Local volume down",
+    ),
+    action(SoundBank, "volume_up", &["+"], "General volume up"),
+    action(SoundBank, "volume_down", &["-"], "General volume down"),
+    action(SoundBank, "bus_volume_up", &["Ctrl+Up"], "Bus volume up"),
+    action(
+        SoundBank,
+        "bus_volume_down",
+        &["Ctrl+Down"],
+        "Bus volume down",
+    ),
+    action(SoundBank, "edit_song", &["F"], "Edit song properties"),
+    action(SoundBank, "seek_backward", &["Left"], "Seek backward 5s"),
+    action(SoundBank, "seek_forward", &["Right"], "Seek forward 5s"),
+    action(
+        SoundBank,
+        "seek_backward_fine",
+        &["Shift+Left"],
+        "Seek backward 1s",
+    ),
+    action(
+        SoundBank,
+        "seek_forward_fine",
+        &["Shift+Right"],
+        "Seek forward 1s",
+    ),
+    action(SoundBank, "restart", &["R"], "Restart from Trim In"),
+    action(
+        SoundBank,
+        "previous_marker",
+        &["["],
+        "Jump to the previous marker",
+    ),
+    action(SoundBank, "next_marker", &["]"], "Jump to the next marker"),
+    action(
+        SoundBank,
+        "pick_layer",
+        &["L"],
+        "Pick / unpick a song for a layered cue",
+    ),
+    action(
+        SoundBank,
+        "build_layered_cue",
+        &["Shift+L"],
+        "Build a layered cue",
+    ),
+    action(
+        SoundBank,
+        "remove_layered_cue",
+        &["X"],
+        "Remove a layered cue",
+    ),
+    action(
+        SoundBank,
+        "route_bus",
+        &["B"],
+        "Route the song to the next bus",
+    ),
+    action(SoundBank, "mute_bus", &["M"], "Mute the bus"),
+    action(SoundBank, "solo_bus", &["S"], "Solo the bus"),
+    action(
+        SoundBank,
+        "playlist",
+        &["Shift+P"],
+        "Start / stop the playlist",
+    ),
+    action(
+        SoundBank,
+        "playlist_skip",
+        &["N"],
+        "Skip to the next playlist track",
+    ),
+    action(
+        SoundBank,
+        "playlist_fade_out_at_end",
+        &["E"],
+        "Fade out the playlist at the end of the track",
+    ),
+    action(SoundBank, "playlist_shuffle", &["O"], "Playlist shuffle"),
+    action(SoundBank, "playlist_group", &["G"], "Playlist group"),
+    action(
+        SoundBank,
+        "crossfade_shorter",
+        &["Ctrl+Left"],
+        "Playlist crossfade -1s",
+    ),
+    action(
+        SoundBank,
+        "crossfade_longer",
+        &["Ctrl+Right"],
+        "Playlist crossfade +1s",
+    ),
+    action(
+        SoundBank,
+        "suggest_trim",
+        &["A"],
+        "Suggest a trim for the song",
+    ),
+    action(
+        SoundBank,
+        "suggest_all_trims",
+        &["Shift+A"],
+        "Suggest a trim for every song",
+    ),
+    action(
+        SoundBank,
+        "threshold_down",
+        &["<"],
+        "Silence threshold -1 dB",
+    ),
+    action(SoundBank, "threshold_up", &[">"], "Silence threshold +1 dB"),
+    action(SoundBank, "bounce_wav", &["W"], "Bounce the song to WAV"),
+    action(
+        SoundBank,
+        "bounce_flac",
+        &["Shift+W"],
+        "Bounce the song to FLAC",
+    ),
+    action(
+        SongProperties,
+        "edit_field",
+        &["Enter"],
+        "Edit / confirm the field",
+    ),
+    action(
+        SongProperties,
+        "close",
+        &["F", "Esc"],
+        "Close the song properties",
+    ),
+    action(SongProperties, "next_field", &["Down"], "Next field"),
+    action(SongProperties, "previous_field", &["Up"], "Previous field"),
+    action(SongProperties, "suggest_trim", &["A"], "Suggest a trim"),
+    action(
+        SongProperties,
+        "previous_marker",
+        &["Left"],
+        "Select the previous marker",
+    ),
+    action(
+        SongProperties,
+        "next_marker",
+        &["Right"],
+        "Select the next marker",
+    ),
+    action(
+        SongProperties,
+        "play_from_marker",
+        &["P"],
+        "Play from the marker",
+    ),
+    action(
+        SongProperties,
+        "remove_marker",
+        &["Delete"],
+        "Remove the marker",
+    ),
+    action(
+        Osc,
+        "edit",
+        &["Enter"],
+        "Edit / confirm the listening address or the mapping",
+    ),
+    action(
+        Osc,
+        "learn",
+        &["L"],
+        "Pick an action and learn the next OSC message for it",
+    ),
+    action(Osc, "delete_mapping", &["Delete"], "Delete the mapping"),
+    action(Osc, "cancel_learn", &["Esc"], "Cancel learning / editing"),
+    action(
+        Osc,
+        "test_destination",
+        &["T"],
+        "Send a message to test an OSC destination",
+    ),
+    action(Dmx, "next_fader", &["Right"], "Next fader"),
+    action(Dmx, "previous_fader", &["Left"], "Previous fader"),
+    action(Dmx, "fader_up", &["Up"], "Fader +1"),
+    action(Dmx, "fader_down", &["Down"], "Fader -1"),
+    action(Dmx, "fader_up_10", &["Ctrl+Up"], "Fader +10"),
+    action(Dmx, "fader_down_10", &["Ctrl+Down"], "Fader -10"),
+    action(Dmx, "fader_full", &["F"], "Fader at 255"),
+    action(Dmx, "fader_zero", &["R", "."], "Fader at 0"),
+    action(Dmx, "fader_clear", &["Backspace"], "Fader at 0"),
+    action(Dmx, "address_up", &["Alt+Up"], "DMX address +1"),
+    action(Dmx, "address_down", &["Alt+Down"], "DMX address -1"),
+    action(Dmx, "address_reset", &["Alt+Backspace"], "DMX address at 1"),
+    action(
+        Dmx,
+        "store_safe_look",
+        &["Shift+S"],
+        "Store the faders as the panic safe look",
+    ),
+    action(Monitor, "pause", &["Space"], "Pause / resume the log"),
+    action(
+        Monitor,
+        "filter",
+        &["/"],
+        "Filter by address or pattern, Enter to confirm",
+    ),
+    action(Monitor, "export", &["E"], "Export the shown log to a file"),
+    action(Monitor, "clear", &["C"], "Clear the log"),
+    action(Monitor, "up", &["Up", "K"], "Previous message"),
+    action(Monitor, "down", &["Down", "J"], "Next message"),
+    action(Monitor, "page_up", &["PageUp"], "Previous page"),
+    action(Monitor, "page_down", &["PageDown"], "Next page"),
+    action(Monitor, "oldest", &["Home"], "Oldest message"),
+    action(Monitor, "follow", &["End"], "Follow the newest messages"),
+];
+
+/// A key with its modifiers. Letters with Shift are stored uppercase, other characters without Shift
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::SHIFT | KeyModifiers::ALT);
+        match code {
+            KeyCode::Char(c) if c.is_ascii_alphabetic() => {
+                if modifiers.contains(KeyModifiers::SHIFT) || c.is_ascii_uppercase() {
+                    Chord {
+                        code: KeyCode::Char(c.to_ascii_uppercase()),
+                        modifiers: modifiers | KeyModifiers::SHIFT,
+                    }
+                } else {
+                    Chord { code, modifiers }
+                }
+            }
+            KeyCode::Char(_) => Chord {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => Chord { code, modifiers },
+        }
+    }
+
+    /// `Ctrl+Shift+P`, `Alt+Up`, `F1`, `Space`, `+` or `P`. A letter alone is the lowercase key
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (modifier_text, key) = match text.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None if text == "+" => ("", "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_text.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "shift" => KeyModifiers::SHIFT,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(format!("{} : unknown modifier {}", text, modifier)),
+            };
+        }
+        let code = match key.trim().to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "tab" => KeyCode::Tab,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+                KeyCode::F(f[1..].parse().unwrap())
+            }
+            _ => {
+                let mut chars = key.trim().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+                    _ => return Err(format!("{} : unknown key {}", text, key)),
+                }
+            }
+        };
+        Ok(Chord::new(code, modifiers))
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Chord::new(event.code, event.modifiers)
+    }
+
+    pub fn to_event(self) -> KeyEvent {
+        KeyEvent::new_with_kind(self.code, self.modifiers, KeyEventKind::Press)
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+            (KeyModifiers::ALT, "Alt+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Esc => write!(f, "Esc"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Keys bound to every named action, the file only lists what differs from the defaults
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Context, action and its keys, in the order of `ACTIONS`
+    bindings: Vec<(Context, &'static str, Vec<Chord>)>,
+    /// Problems found in the keymap file, the defaults are used when there is one
+    pub errors: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: ACTIONS
+                .iter()
+                .map(|a| {
+                    (
+                        a.context,
+                        a.name,
+                        a.defaults
+                            .iter()
+                            .map(|d| Chord::parse(d).expect("default key"))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Read the keymap file, a missing file is the default keymap.
+    /// Any error falls back to the defaults and is kept in `errors`
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Keymap::default();
+        };
+        match Keymap::from_toml(&text) {
+            Ok(keymap) => keymap,
+            Err(errors) => Keymap {
+                errors: errors
+                    .into_iter()
+                    .map(|e| format!("{} : {}", path.to_string_lossy(), e))
+                    .collect(),
+                ..Keymap::default()
+            },
+        }
+    }
+
+    /// `[section] action = "Key"` or `action = ["Key", "Key"]`, then the conflicts are checked
+    pub fn from_toml(text: &str) -> Result<Self, Vec<String>> {
+        let sections: BTreeMap<String, BTreeMap<String, toml::Value>> =
+            toml::from_str(text).map_err(|e| vec![e.to_string()])?;
+        let mut keymap = Keymap::default();
+        let mut errors = Vec::new();
+        for (section, actions) in sections {
+            let Some(context) = Context::ALL.iter().find(|c| c.name() == section) else {
+                errors.push(format!("unknown section [{}]", section));
+                continue;
+            };
+            for (action, value) in actions {
+                let Some(binding) = keymap
+                    .bindings
+                    .iter_mut()
+                    .find(|(c, name, _)| c == context && *name == action)
+                else {
+                    errors.push(format!("unknown action {}.{}", section, action));
+                    continue;
+                };
+                let keys = match value {
+                    toml::Value::String(key) => vec![key],
+                    toml::Value::Array(keys) => keys
+                        .into_iter()
+                        .filter_map(|k| k.as_str().map(str::to_owned))
+                        .collect(),
+                    _ => {
+                        errors.push(format!(
+                            "{}.{} must be a key or a list of keys",
+                            section, action
+                        ));
+                        continue;
+                    }
+                };
+                match keys.iter().map(|k| Chord::parse(k)).collect() {
+                    Ok(chords) => binding.2 = chords,
+                    Err(e) => errors.push(format!("{}.{} : {}", section, action, e)),
+                }
+            }
+        }
+        errors.extend(keymap.conflicts());
+        if errors.is_empty() {
+            Ok(keymap)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Keys bound twice in a context, or bound in a tab while a global action uses them
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (i, (context, name, chords)) in self.bindings.iter().enumerate() {
+            for (other_context, other_name, other_chords) in &self.bindings[i + 1..] {
+                let clash = context == other_context
+                    || (*context == Global && *other_context != Navigation)
+                    || (*other_context == Global && *context != Navigation);
+                if !clash {
+                    continue;
+                }
+                for chord in chords.iter().filter(|c| other_chords.contains(c)) {
+                    conflicts.push(format!(
+                        "{} is bound to {}.{} and {}.{}",
+                        chord,
+                        context.name(),
+                        name,
+                        other_context.name(),
+                        other_name
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+
+    fn action_of(&self, context: Context, chord: Chord) -> Option<usize> {
+        self.bindings
+            .iter()
+            .position(|(c, _, chords)| *c == context && chords.contains(&chord))
+    }
+
+    /// The key the tabs expect for the key pressed. Global keys are always read, the tab and the
+    /// navigation keys only when the operator is not typing. None when the key lost its action.
+    pub fn translate(&self, context: Context, typing: bool, event: KeyEvent) -> Option<KeyEvent> {
+        let chord = Chord::from_event(&event);
+        let contexts: &[Context] = if typing {
+            &[Global]
+        } else {
+            &[Global, context, Navigation]
+        };
+        for matched in contexts {
+            if let Some(action) = self.action_of(*matched, chord) {
+                let defaults: Vec<Chord> = ACTIONS[action]
+                    .defaults
+                    .iter()
+                    .map(|d| Chord::parse(d).expect("default key"))
+                    .collect();
+                // The tabs read every default key of their actions as is
+                let key = if defaults.contains(&chord) {
+                    chord
+                } else {
+                    defaults[0]
+                };
+                // A navigation key the tab also reads, like Shift+Down lowering the local volume,
+                // would run the tab action
+                if *matched == Navigation && is_default(&[context], key) {
+                    return None;
+                }
+                return Some(if key == chord {
+                    event
+                } else {
+                    KeyEvent {
+                        kind: event.kind,
+                        ..key.to_event()
+                    }
+                });
+            }
+        }
+        // A default key moved to another action or removed does nothing
+        if is_default(contexts, chord) {
+            None
+        } else {
+            Some(event)
+        }
+    }
+
+    /// Context, keys and description of every action, for the help screen
+    pub fn help(&self) -> Vec<(&'static str, String, &'static str)> {
+        self.bindings
+            .iter()
+            .zip(ACTIONS)
+            .map(|((context, _, chords), info)| {
+                let keys = if chords.is_empty() {
+                    "-".to_owned()
+                } else {
+                    chords
+                        .iter()
+                        .map(Chord::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" / ")
+                };
+                (context.name(), keys, info.description)
+            })
+            .collect()
+    }
+}
+
+/// The key is a default key of an action of these contexts
+fn is_default(contexts: &[Context], chord: Chord) -> bool {
+    ACTIONS.iter().any(|a| {
+        contexts.contains(&a.context)
+            && a.defaults
+                .iter()
+                .any(|d| Chord::parse(d).is_ok_and(|d| d == chord))
+    })
+}
+
+/// Keymap file in the home directory
+pub fn keymap_path() -> PathBuf {
+    super::show::home_dir().join(".utilscord_keymap.toml")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chords_parse_and_print() {
+        for (text, printed) in [
+            ("Ctrl+Shift+p", "Ctrl+Shift+P"),
+            ("shift+w", "Shift+W"),
+            ("W", "W"),
+            ("Alt+Up", "Alt+Up"),
+            ("Ctrl++", "Ctrl++"),
+            ("space", "Space"),
+            ("f12", "F12"),
+        ] {
+            assert_eq!(Chord::parse(text).unwrap().to_string(), printed);
+        }
+        assert_eq!(
+            Chord::from_event(&KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT)),
+            Chord::parse("Shift+L").unwrap()
+        );
+        assert!(Chord::parse("Hyper+A").is_err());
+        assert!(Keymap::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn remapped_keys_are_translated_and_checked() {
+        let keymap = Keymap::from_toml(
+            "[global]\nquit = \"Ctrl+W\"\n[sound_bank]\nedit_song = \"Ctrl+F\"\n",
+        )
+        .unwrap();
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+        // The new key acts as the old one
+        assert_eq!(
+            keymap.translate(
+                SoundBank,
+                false,
+                press(KeyCode::Char('f'), KeyModifiers::CONTROL)
+            ),
+            Some(press(KeyCode::Char('f'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            keymap.translate(Dmx, true, press(KeyCode::Char('w'), KeyModifiers::CONTROL)),
+            Some(press(KeyCode::Char('q'), KeyModifiers::CONTROL))
+        );
+        // The old key does nothing, other tabs keep it
+        assert_eq!(
+            keymap.translate(
+                SoundBank,
+                false,
+                press(KeyCode::Char('f'), KeyModifiers::NONE)
+            ),
+            None
+        );
+        assert_eq!(
+            keymap.translate(Dmx, false, press(KeyCode::Char('f'), KeyModifiers::NONE)),
+            Some(press(KeyCode::Char('f'), KeyModifiers::NONE))
+        );
+        // Typed text is left alone
+        assert_eq!(
+            keymap.translate(
+                SoundBank,
+                true,
+                press(KeyCode::Char('f'), KeyModifiers::NONE)
+            ),
+            Some(press(KeyCode::Char('f'), KeyModifiers::NONE))
+        );
+
+        // A key shared by the tab and the navigation stays with the tab once remapped
+        let keymap = Keymap::from_toml("[sound_bank]\nlocal_volume_down = \"Alt+Down\"\n").unwrap();
+        let shift_down = press(KeyCode::Down, KeyModifiers::SHIFT);
+        assert_eq!(keymap.translate(SoundBank, false, shift_down), None);
+        assert_eq!(
+            keymap.translate(SoundBank, false, press(KeyCode::Down, KeyModifiers::ALT)),
+            Some(shift_down)
+        );
+        assert_eq!(keymap.translate(Dmx, false, shift_down), Some(shift_down));
+        let shift_j = press(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.translate(SoundBank, false, shift_j), Some(shift_j));
+
+        let errors =
+            Keymap::from_toml("[sound_bank]\npause = \"M\"\nplay = \"Ctrl+Z\"\nfly = \"Y\"\n")
+                .unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                "unknown action sound_bank.fly",
+                "Ctrl+Z is bound to global.undo and sound_bank.play",
+                "M is bound to sound_bank.pause and sound_bank.mute_bus",
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use ratatui::widgets::ListState;
+use rosc::{OscMessage, OscType};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Range of the first argument of the remote device and the range the action gets
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    pub from: [f32; 2],
+    pub to: [f32; 2],
+}
+
+impl Scale {
+    /// Float faders of lighting desks go from 0 to 1
+    const DMX: Scale = Scale {
+        from: [0.0, 1.0],
+        to: [0.0, 255.0],
+    };
+
+    /// Linear, the value is kept within `from`
+    pub fn apply(&self, value: f32) -> f32 {
+        let [from_min, from_max] = self.from;
+        let [to_min, to_max] = self.to;
+        if from_min == from_max {
+            return to_min;
+        }
+        let ratio = ((value - from_min) / (from_max - from_min)).clamp(0.0, 1.0);
+        to_min + ratio * (to_max - to_min)
+    }
+}
+
+/// An address of a remote device that runs a method of the namespace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Mapping {
+    /// Address sent by the remote device, matched as is
+    pub address: String,
+    /// Method under the namespace, like Play/4, Stop or DMXChan/12
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<Scale>,
+}
+
+fn number(arg: &OscType) -> Option<f32> {
+    match arg {
+        OscType::Int(i) => Some(*i as f32),
+        OscType::Float(f) => Some(*f),
+        OscType::Long(l) => Some(*l as f32),
+        OscType::Double(d) => Some(*d as f32),
+        OscType::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn parse_range(text: &str) -> Result<[f32; 2], String> {
+    let range = text
+        .split_once("..")
+        .and_then(|(min, max)| Some([min.parse().ok()?, max.parse().ok()?]));
+    range.ok_or_else(|| format!("{} is not a range, ex : 0..1", text))
+}
+
+impl Mapping {
+    /// A new mapping for the message, a float sent to a DMX channel is scaled to 0..255
+    pub fn learn(action: &str, message: &OscMessage) -> Self {
+        let float = matches!(message.args.first(), Some(OscType::Float(_)));
+        Mapping {
+            address: message.addr.clone(),
+            action: action.to_owned(),
+            scale: (float && action.starts_with("DMXChan/")).then_some(Scale::DMX),
+        }
+    }
+
+    /// Written as in the mapping list : ADDRESS -> ACTION, then FROM => TO when it scales
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let example = "ex : /desk/fader/12 -> DMXChan/12 0..1 => 0..255";
+        let (address, rest) = text
+            .split_once("->")
+            .ok_or_else(|| format!("Missing -> between the address and the action, {}", example))?;
+        let address = address.trim();
+        if !address.starts_with('/') || address.contains(char::is_whitespace) {
+            return Err(format!("{} is not an OSC address, {}", address, example));
+        }
+        let mut parts = rest.split_whitespace();
+        let action = parts
+            .next()
+            .ok_or_else(|| format!("Missing action, {}", example))?
+            .trim_start_matches('/');
+        let scale = match parts.collect::<Vec<_>>()[..] {
+            [] => None,
+            [from, "=>", to] => Some(Scale {
+                from: parse_range(from)?,
+                to: parse_range(to)?,
+            }),
+            _ => return Err(format!("The scaling is FROM => TO, {}", example)),
+        };
+        Ok(Mapping {
+            address: address.to_owned(),
+            action: action.to_owned(),
+            scale,
+        })
+    }
+
+    /// The message for the action, the first argument is scaled when the mapping scales
+    pub fn apply(&self, namespace: &str, message: &OscMessage) -> Result<OscMessage, String> {
+        let mut args = message.args.clone();
+        if let Some(scale) = &self.scale {
+            let value = args.first().and_then(number).ok_or_else(|| {
+                format!(
+                    "{} is mapped with a scaling, it needs a number and got {:?}",
+                    message.addr, message.args
+                )
+            })?;
+            args[0] = OscType::Float(scale.apply(value));
+        }
+        Ok(OscMessage {
+            addr: format!("/{}/{}", namespace, self.action),
+            args,
+        })
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.address, self.action)?;
+        if let Some(Scale { from, to }) = &self.scale {
+            write!(f, " {}..{} => {}..{}", from[0], from[1], to[0], to[1])?;
+        }
+        Ok(())
+    }
+}
+
+/// Mappings of the OSC tab and what is being learnt or edited
+#[derive(Clone, Debug, Default)]
+pub struct Learn {
+    pub mappings: Vec<Mapping>,
+    pub state: ListState,
+    /// Filter typed in the action picker, None when it is closed
+    pub picking: Option<String>,
+    /// Actions of the address space when the picker opened
+    pub actions: Vec<String>,
+    pub picker_state: ListState,
+    /// Action picked, the next message received is mapped to it
+    pub waiting: Option<String>,
+    /// Text of the selected mapping being edited
+    pub editing: Option<String>,
+}
+
+impl Learn {
+    /// Open the action picker, what was waiting to be learnt is dropped
+    pub fn pick(&mut self, actions: Vec<String>) {
+        self.waiting = None;
+        self.actions = actions;
+        self.picking = Some(String::new());
+        self.picker_state.select(Some(0));
+    }
+
+    /// Actions of the picker matching the filter
+    pub fn choices(&self) -> Vec<&str> {
+        let filter = self.picking.as_deref().unwrap_or_default().to_lowercase();
+        self.actions
+            .iter()
+            .map(String::as_str)
+            .filter(|action| action.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// The message maps to the action waiting for it, returns the new mapping
+    pub fn learn(&mut self, message: &OscMessage) -> Option<&Mapping> {
+        let action = self.waiting.take()?;
+        let mapping = Mapping::learn(&action, message);
+        // Learning an address again replaces its mapping to the same action
+        self.mappings
+            .retain(|m| m.address != mapping.address || m.action != mapping.action);
+        self.mappings.push(mapping);
+        self.state.select(Some(self.mappings.len() - 1));
+        self.mappings.last()
+    }
+
+    /// Messages for the actions mapped to the address, None when it is not mapped
+    pub fn map(
+        &self,
+        namespace: &str,
+        message: &OscMessage,
+    ) -> Option<Vec<Result<OscMessage, String>>> {
+        let mapped: Vec<_> = self
+            .mappings
+            .iter()
+            .filter(|m| m.address == message.addr)
+            .map(|m| m.apply(namespace, message))
+            .collect();
+        (!mapped.is_empty()).then_some(mapped)
+    }
+
+    pub fn delete_selected(&mut self) -> Option<Mapping> {
+        let index = self.state.selected().filter(|i| *i < self.mappings.len())?;
+        let mapping = self.mappings.remove(index);
+        if self.mappings.is_empty() {
+            self.state.select(None);
+        } else {
+            self.state.select(Some(index.min(self.mappings.len() - 1)));
+        }
+        Some(mapping)
+    }
+
+    /// Replace the selected mapping with the edited text, the text is kept on an error
+    pub fn confirm_edit(&mut self) -> Result<(), String> {
+        let Some(text) = &self.editing else {
+            return Ok(());
+        };
+        let mapping = Mapping::parse(text)?;
+        match self.state.selected().and_then(|i| self.mappings.get_mut(i)) {
+            Some(selected) => *selected = mapping,
+            None => self.mappings.push(mapping),
+        }
+        self.editing = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(addr: &str, args: Vec<OscType>) -> OscMessage {
+        OscMessage {
+            addr: addr.to_owned(),
+            args,
+        }
+    }
+
+    #[test]
+    fn learnt_mappings_scale_and_edit() {
+        let mut learn = Learn::default();
+        learn.pick(vec!["Play/4".to_owned(), "DMXChan/12".to_owned()]);
+        learn.picking = Some("dmx".to_owned());
+        assert_eq!(learn.choices(), ["DMXChan/12"]);
+        learn.waiting = Some("DMXChan/12".to_owned());
+        let fader = message("/desk/fader/12", vec![OscType::Float(0.5)]);
+        assert_eq!(
+            learn.learn(&fader).unwrap().to_string(),
+            "/desk/fader/12 -> DMXChan/12 0..1 => 0..255"
+        );
+        assert!(learn.learn(&fader).is_none());
+        assert_eq!(
+            learn.map("Utilscord", &fader).unwrap(),
+            [Ok(message(
+                "/Utilscord/DMXChan/12",
+                vec![OscType::Float(127.5)]
+            ))]
+        );
+        assert!(learn
+            .map("Utilscord", &message("/desk/fader/12", vec![]))
+            .unwrap()[0]
+            .is_err());
+        assert!(learn
+            .map("Utilscord", &message("/desk/go", vec![]))
+            .is_none());
+
+        learn.editing = Some("/desk/go -> /Play/4".to_owned());
+        learn.confirm_edit().unwrap();
+        assert_eq!(
+            learn.mappings,
+            [Mapping {
+                address: "/desk/go".to_owned(),
+                action: "Play/4".to_owned(),
+                scale: None,
+            }]
+        );
+        for text in [
+            "/desk/go Play/4",
+            "desk -> Play/4",
+            "/desk/go ->",
+            "/desk/go -> Volume 0..1 -> 0..2",
+            "/desk/go -> Volume 0..x => 0..2",
+        ] {
+            learn.editing = Some(text.to_owned());
+            assert!(learn.confirm_edit().is_err(), "{}", text);
+        }
+        assert_eq!(
+            Mapping::parse("/desk/v -> Volume 0..127 => 0..1")
+                .unwrap()
+                .scale
+                .unwrap()
+                .apply(254.0),
+            1.0
+        );
+        learn.editing = None;
+        assert!(learn.delete_selected().is_some());
+        assert!(learn.mappings.is_empty());
+    }
+}
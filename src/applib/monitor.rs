@@ -0,0 +1,241 @@
+use super::osc_address;
+use super::osc_send::argument;
+use ratatui::widgets::TableState;
+use rosc::OscType;
+use std::collections::VecDeque;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Oldest entries are dropped past this, about an hour of a busy show network
+pub const MAX_ENTRIES: usize = 5000;
+
+/// One OSC message and what it did
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub time: SystemTime,
+    /// None for the messages of a bundle run at their timetag
+    pub from: Option<SocketAddr>,
+    pub address: String,
+    pub args: Vec<OscType>,
+    /// Methods run, the learnt mapping or the error
+    pub handled: Result<Vec<String>, String>,
+}
+
+/// UTC time of the day with milliseconds
+pub fn clock(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+impl Entry {
+    pub fn source(&self) -> String {
+        match self.from {
+            Some(from) => from.to_string(),
+            None => "bundle".to_owned(),
+        }
+    }
+
+    /// Typed arguments, like i:12 f:0.5 s:"Intro"
+    pub fn arguments(&self) -> String {
+        self.args.iter().map(argument).collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn outcome(&self) -> String {
+        match &self.handled {
+            Ok(handled) if handled.len() == 1 && handled[0] == self.address => "Ok".to_owned(),
+            Ok(handled) => format!("-> {}", handled.join(", ")),
+            Err(e) => format!("Error : {}", e),
+        }
+    }
+
+    pub fn line(&self) -> String {
+        format!(
+            "{} {} {} {} | {}",
+            clock(self.time),
+            self.source(),
+            self.address,
+            self.arguments(),
+            self.outcome()
+        )
+    }
+}
+
+/// Scrolling log of the OSC messages, for the monitor tab
+#[derive(Clone, Debug, Default)]
+pub struct Monitor {
+    entries: VecDeque<Entry>,
+    /// Entries received while paused, shown on resume
+    held: VecDeque<Entry>,
+    pub paused: bool,
+    /// Part of the address to show, or an OSC address pattern
+    pub filter: String,
+    /// The filter is being typed
+    pub filtering: bool,
+    /// Selected line, None follows the newest entry
+    pub state: TableState,
+    /// Outcome of the last export or clear
+    pub status: String,
+}
+
+impl Monitor {
+    pub fn push(&mut self, entry: Entry) {
+        if self.paused {
+            self.held.push_back(entry);
+        } else {
+            self.entries.push_back(entry);
+        }
+        let total = self.entries.len() + self.held.len();
+        // Drop the oldest shown entries first, the held ones are newer
+        for _ in MAX_ENTRIES..total {
+            if self.entries.pop_front().is_none() {
+                self.held.pop_front();
+            }
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if !self.paused {
+            self.entries.extend(self.held.drain(..));
+        }
+    }
+
+    pub fn held(&self) -> usize {
+        self.held.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.held.clear();
+        self.state.select(None);
+        self.status = "Cleared".to_owned();
+    }
+
+    /// Entries passing the filter, oldest first
+    pub fn filtered(&self) -> Vec<&Entry> {
+        if self.filter.is_empty() {
+            return self.entries.iter().collect();
+        }
+        if osc_address::is_pattern(&self.filter) {
+            let Ok(pattern) = osc_address::Pattern::parse(&self.filter) else {
+                return Vec::new();
+            };
+            return self
+                .entries
+                .iter()
+                .filter(|e| pattern.matches(&e.address))
+                .collect();
+        }
+        let filter = self.filter.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|e| e.address.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// Move the selection by `lines`, back to following the newest entry past the end
+    pub fn scroll(&mut self, lines: isize) {
+        let count = self.filtered().len();
+        if count == 0 {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or(count - 1) as isize + lines;
+        if selected >= count as isize {
+            self.state.select(None);
+        } else {
+            self.state.select(Some(selected.max(0) as usize));
+        }
+    }
+
+    /// Write the filtered entries to a text file in `dir`, returns its path
+    pub fn export(&mut self, dir: &Path) -> Result<PathBuf, String> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = dir.join(format!("utilscord_osc_monitor_{}.txt", seconds));
+        let mut text: String = self.filtered().iter().map(|e| e.line() + "\n").collect();
+        if !self.filter.is_empty() {
+            text.insert_str(0, &format!("# Filter : {}\n", self.filter));
+        }
+        let result = fs::write(&path, text)
+            .map(|()| path.clone())
+            .map_err(|e| format!("Cannot export to {} : {}", path.to_string_lossy(), e));
+        self.status = match &result {
+            Ok(path) => format!("Exported to {}", path.to_string_lossy()),
+            Err(e) => e.clone(),
+        };
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(address: &str, handled: Result<Vec<String>, String>) -> Entry {
+        Entry {
+            time: UNIX_EPOCH + Duration::from_millis(3_723_045),
+            from: "192.168.1.20:53000".parse().ok(),
+            address: address.to_owned(),
+            args: vec![OscType::Int(12), OscType::Float(0.5)],
+            handled,
+        }
+    }
+
+    #[test]
+    fn entries_are_filtered_held_and_exported() {
+        let mut monitor = Monitor::default();
+        monitor.push(entry(
+            "/Utilscord/Play/0",
+            Ok(vec!["/Utilscord/Play/0".to_owned()]),
+        ));
+        monitor.toggle_pause();
+        monitor.push(entry(
+            "/desk/go",
+            Err("No DMX connection found !".to_owned()),
+        ));
+        assert_eq!(monitor.filtered().len(), 1);
+        assert_eq!(monitor.held(), 1);
+        monitor.toggle_pause();
+        assert_eq!(
+            monitor.filtered()[1].line(),
+            "01:02:03.045 192.168.1.20:53000 /desk/go i:12 f:0.5 | Error : No DMX connection found !"
+        );
+
+        monitor.filter = "PLAY".to_owned();
+        assert_eq!(monitor.filtered()[0].outcome(), "Ok");
+        monitor.filter = "/desk/*".to_owned();
+        assert_eq!(monitor.filtered()[0].address, "/desk/go");
+
+        monitor.filter.clear();
+        monitor.scroll(-1);
+        assert_eq!(monitor.state.selected(), Some(0));
+        monitor.scroll(5);
+        assert_eq!(monitor.state.selected(), None);
+
+        let dir = std::env::temp_dir();
+        monitor.filter = "desk".to_owned();
+        let path = monitor.export(&dir).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("# Filter : desk\n"));
+
+        for _ in 0..MAX_ENTRIES {
+            monitor.push(entry("/desk/go", Ok(vec![])));
+        }
+        monitor.filter.clear();
+        assert_eq!(monitor.filtered().len(), MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,365 @@
+use super::component::{Content, Tab};
+use open_dmx::DMX_CHANNELS;
+
+/// Namespace of the OSC methods when the show or the command line do not set one
+pub const DEFAULT_NAMESPACE: &str = "Utilscord";
+
+/// Characters that make an OSC address a pattern, OSC 1.0
+const PATTERN_CHARACTERS: &[char] = &['*', '?', '[', ']', '{', '}'];
+
+/// Part of Utilscord running the methods of a root
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Handler {
+    SoundList,
+    Dmx,
+    Sync,
+}
+
+/// First part of every method after the namespace with its handler, the dispatch table of the
+/// OSC messages. A root is only reached once it is listed here and in the address space
+const ROOTS: &[(&str, Handler)] = &[
+    ("Play", Handler::SoundList),
+    ("Stop", Handler::SoundList),
+    ("Seek", Handler::SoundList),
+    ("Restart", Handler::SoundList),
+    ("LocalVolume", Handler::SoundList),
+    ("Volume", Handler::SoundList),
+    ("Bus", Handler::SoundList),
+    ("Playlist", Handler::SoundList),
+    ("Bounce", Handler::SoundList),
+    ("SuggestTrim", Handler::SoundList),
+    ("Panic", Handler::SoundList),
+    ("PauseAll", Handler::SoundList),
+    ("ResumeAll", Handler::SoundList),
+    ("PauseFade", Handler::SoundList),
+    ("DMXChan", Handler::Dmx),
+    ("Sync", Handler::Sync),
+];
+
+/// Roots whose methods never change the sound items or the DMX faders, the values of the undo
+/// history
+const HISTORY_FREE_ROOTS: &[&str] = &[
+    "Play",
+    "Stop",
+    "Seek",
+    "Restart",
+    "Volume",
+    "Bus",
+    "Playlist",
+    "Bounce",
+    "PauseAll",
+    "ResumeAll",
+    "PauseFade",
+    "Sync",
+];
+
+/// Methods taking the index of a sound item, like Play/4
+const ITEM_METHODS: [&str; 4] = ["Play", "LocalVolume", "SuggestTrim", "Bounce"];
+const BUS_PARAMETERS: [&str; 3] = ["Volume", "Mute", "Solo"];
+
+/// Methods without parameters, under the namespace
+const FIXED_METHODS: &[&str] = &[
+    "Play/Next",
+    "Play/Previous",
+    "Stop",
+    "Seek",
+    "Restart",
+    "LocalVolume/Selected",
+    "Volume",
+    "Playlist/Start",
+    "Playlist/Stop",
+    "Playlist/Skip",
+    "Playlist/FadeOutAtEnd",
+    "Panic",
+    "Panic/Clear",
+    "PauseAll",
+    "ResumeAll",
+    "PauseFade",
+    "SuggestTrim/All",
+    "Sync",
+];
+
+/// Handler of the method starting with `root`, None when there is no such method
+pub fn handler(root: &str) -> Option<Handler> {
+    ROOTS
+        .iter()
+        .find(|(name, _)| *name == root)
+        .map(|(_, handler)| *handler)
+}
+
+/// The address only reaches methods that leave the values of the undo history alone, a pattern
+/// may reach any of them
+pub fn leaves_history(namespace: &str, address: &str) -> bool {
+    let mut parts = address.split('/');
+    !is_pattern(address)
+        && parts.next() == Some("")
+        && parts.next() == Some(namespace)
+        && parts
+            .next()
+            .is_some_and(|root| HISTORY_FREE_ROOTS.contains(&root))
+}
+
+pub fn is_pattern(address: &str) -> bool {
+    address.contains(PATTERN_CHARACTERS)
+}
+
+/// A namespace is one part of an OSC address, without '/' or pattern characters
+pub fn check_namespace(namespace: &str) -> Result<(), String> {
+    if namespace.is_empty()
+        || namespace.contains(['/', ' ', '#', ','])
+        || is_pattern(namespace)
+        || !namespace.is_ascii()
+    {
+        return Err(format!(
+            "{} is not a valid OSC namespace, ex : Utilscord",
+            namespace
+        ));
+    }
+    Ok(())
+}
+
+/// Every OSC method with the sound items, markers, buses and DMX channels of the tabs
+pub fn address_space(namespace: &str, tabs: &[Tab]) -> Vec<String> {
+    actions(tabs)
+        .into_iter()
+        .map(|method| format!("/{}/{}", namespace, method))
+        .collect()
+}
+
+/// The methods of the address space without the namespace, like Play/4
+pub fn actions(tabs: &[Tab]) -> Vec<String> {
+    let mut methods: Vec<String> = FIXED_METHODS.iter().map(|m| m.to_string()).collect();
+    for tab in tabs {
+        match &tab.content {
+            Content::MainMenu(soundlist, _input) => {
+                for (index, si) in soundlist.sound_files.iter().enumerate() {
+                    for method in ITEM_METHODS {
+                        methods.push(format!("{}/{}", method, index));
+                    }
+                    for marker in &si.markers {
+                        methods.push(format!("Play/{}/Marker/{}", index, marker.name));
+                    }
+                }
+                for bus in &soundlist.buses {
+                    for parameter in BUS_PARAMETERS {
+                        methods.push(format!("Bus/{}/{}", bus.name, parameter));
+                    }
+                }
+            }
+            Content::Osc(_) | Content::Monitor(_) => (),
+            Content::Dmx(..) => {
+                methods.extend((1..=DMX_CHANNELS).map(|channel| format!("DMXChan/{}", channel)))
+            }
+        }
+    }
+    methods
+}
+
+/// One element of an OSC address pattern part
+#[derive(Debug, PartialEq)]
+enum Token {
+    Literal(char),
+    /// `?`
+    Any,
+    /// `*`, any run of characters, even empty
+    AnyRun,
+    /// `[a-z]`, `[!0-9]` : the ranges and if they are negated
+    Class(Vec<(char, char)>, bool),
+    /// `{Play,Stop}`
+    Choice(Vec<String>),
+}
+
+fn tokens(part: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = part.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '?' => Token::Any,
+            '*' => Token::AnyRun,
+            '[' => {
+                let negated = chars.next_if_eq(&'!').is_some();
+                let mut ranges = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(first) => {
+                            // A '-' right before ']' is taken literally
+                            if chars.peek() == Some(&'-') {
+                                chars.next();
+                                match chars.peek() {
+                                    Some(']') | None => ranges.extend([(first, first), ('-', '-')]),
+                                    Some(&last) => {
+                                        chars.next();
+                                        ranges.push((first, last))
+                                    }
+                                }
+                            } else {
+                                ranges.push((first, first))
+                            }
+                        }
+                        None => return Err("'[' without ']'".to_owned()),
+                    }
+                }
+                Token::Class(ranges, negated)
+            }
+            '{' => {
+                let mut choices = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => choices.push(c),
+                        None => return Err("'{' without '}'".to_owned()),
+                    }
+                }
+                Token::Choice(choices.split(',').map(str::to_owned).collect())
+            }
+            ']' | '}' => return Err(format!("'{}' without its opening bracket", c)),
+            c => Token::Literal(c),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn matches(tokens: &[Token], name: &str) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return name.is_empty();
+    };
+    let mut chars = name.chars();
+    match token {
+        Token::AnyRun => name
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([name.len()])
+            .any(|i| matches(rest, &name[i..])),
+        Token::Choice(choices) => choices.iter().any(|choice| {
+            name.strip_prefix(choice.as_str())
+                .is_some_and(|n| matches(rest, n))
+        }),
+        Token::Any => chars.next().is_some() && matches(rest, chars.as_str()),
+        Token::Literal(literal) => chars.next() == Some(*literal) && matches(rest, chars.as_str()),
+        Token::Class(ranges, negated) => {
+            chars.next().is_some_and(|c| {
+                ranges
+                    .iter()
+                    .any(|(first, last)| (*first..=*last).contains(&c))
+                    != *negated
+            }) && matches(rest, chars.as_str())
+        }
+    }
+}
+
+/// An OSC address pattern parsed once, to match many addresses
+pub struct Pattern(Vec<Vec<Token>>);
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        pattern
+            .strip_prefix('/')
+            .ok_or_else(|| {
+                format!(
+                    "Invalid OSC address pattern {} : it must start with /",
+                    pattern
+                )
+            })?
+            .split('/')
+            .map(tokens)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Pattern)
+            .map_err(|e| format!("Invalid OSC address pattern {} : {}", pattern, e))
+    }
+
+    /// Part by part between the '/'
+    pub fn matches(&self, address: &str) -> bool {
+        let names: Vec<&str> = address.trim_start_matches('/').split('/').collect();
+        names.len() == self.0.len()
+            && self
+                .0
+                .iter()
+                .zip(names)
+                .all(|(tokens, name)| matches(tokens, name))
+    }
+}
+
+/// Addresses of `space` matched by the pattern
+pub fn matching(pattern: &str, space: Vec<String>) -> Result<Vec<String>, String> {
+    let pattern = Pattern::parse(pattern)?;
+    Ok(space
+        .into_iter()
+        .filter(|address| pattern.matches(address))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn patterns_match_the_address_space() {
+        let space = [
+            "/Utilscord/Play/0",
+            "/Utilscord/Play/1",
+            "/Utilscord/Play/12",
+            "/Utilscord/Stop",
+            "/Utilscord/Bus/SFX/Mute",
+            "/Utilscord/Bus/Music/Mute",
+            "/Utilscord/DMXChan/7",
+        ]
+        .map(str::to_owned)
+        .to_vec();
+        let matched = |pattern| matching(pattern, space.clone()).unwrap();
+        assert_eq!(
+            matched("/Utilscord/Play/?"),
+            ["/Utilscord/Play/0", "/Utilscord/Play/1"]
+        );
+        assert_eq!(matched("/Utilscord/Play/[1-9]"), ["/Utilscord/Play/1"]);
+        assert_eq!(
+            matched("/Utilscord/Play/[1-9]*"),
+            ["/Utilscord/Play/1", "/Utilscord/Play/12"]
+        );
+        assert_eq!(matched("/Utilscord/Play/[!0-]?"), ["/Utilscord/Play/12"]);
+        assert_eq!(matched("/*/*"), ["/Utilscord/Stop"]);
+        assert_eq!(matched("/Utilscord/{Stop,Panic}"), ["/Utilscord/Stop"]);
+        assert_eq!(
+            matched("/Utilscord/Bus/*/Mute"),
+            ["/Utilscord/Bus/SFX/Mute", "/Utilscord/Bus/Music/Mute"]
+        );
+        assert!(matched("/Utilscord/Fly/*").is_empty());
+        assert!(matching("/Utilscord/Play/[0-", space.clone()).is_err());
+        assert!(is_pattern("/Utilscord/Play/{0,1}"));
+        assert!(!is_pattern("/Utilscord/Play/0"));
+    }
+
+    #[test]
+    fn address_space_and_dispatch_table_agree() {
+        let mut roots: Vec<&str> = FIXED_METHODS
+            .iter()
+            .map(|method| method.split('/').next().unwrap())
+            .chain(ITEM_METHODS)
+            .chain(["Bus", "DMXChan"])
+            .collect();
+        for root in &roots {
+            assert!(handler(root).is_some(), "{} has no handler", root);
+        }
+        roots.sort_unstable();
+        roots.dedup();
+        for (root, _) in ROOTS {
+            assert!(roots.contains(root), "{} is not in the address space", root);
+        }
+        assert_eq!(handler("Fly"), None);
+        for root in HISTORY_FREE_ROOTS {
+            assert!(handler(root).is_some(), "{} has no handler", root);
+        }
+    }
+
+    #[test]
+    fn only_methods_without_edits_leave_the_history() {
+        assert!(leaves_history("Utilscord", "/Utilscord/Sync"));
+        assert!(leaves_history("Utilscord", "/Utilscord/Play/3"));
+        assert!(!leaves_history("Utilscord", "/Utilscord/DMXChan/1"));
+        assert!(!leaves_history("Utilscord", "/Utilscord/LocalVolume/0"));
+        assert!(!leaves_history("Utilscord", "/Utilscord/Panic"));
+        assert!(!leaves_history("Utilscord", "/Utilscord/*"));
+        assert!(!leaves_history("Show", "/Utilscord/Sync"));
+    }
+}
@@ -0,0 +1,328 @@
+use rosc::{OscMessage, OscPacket, OscType};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Arc;
+
+/// A show system the cues send OSC to, like a video server
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Destination {
+    /// Written before the address of the messages, like video
+    pub name: String,
+    /// HOST:PORT the messages are sent to over UDP
+    pub address: String,
+}
+
+impl Destination {
+    /// First address of the host, a name goes through DNS so this is done once
+    pub fn resolve(&self) -> Result<SocketAddr, String> {
+        self.address
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| format!("Cannot resolve {} of {}", self.address, self.name))
+    }
+}
+
+/// The destinations resolved when the show loads, sent to from one socket per IP version
+#[derive(Clone, Debug, Default)]
+pub struct Outputs {
+    targets: Vec<(String, Result<SocketAddr, String>)>,
+    v4: Option<Arc<UdpSocket>>,
+    v6: Option<Arc<UdpSocket>>,
+}
+
+impl Outputs {
+    /// Resolve every destination and bind the sockets, returns the outputs and the errors of the
+    /// destinations that cannot be reached
+    pub fn resolve(destinations: &[Destination]) -> (Self, Vec<String>) {
+        let mut outputs = Outputs::default();
+        let bind = |local: &str| {
+            UdpSocket::bind(local)
+                .map(Arc::new)
+                .map_err(|e| format!("Cannot open an OSC socket on {} : {}", local, e))
+        };
+        let mut errors = Vec::new();
+        for destination in destinations {
+            let target = destination.resolve().and_then(|target| {
+                let socket = if target.is_ipv4() {
+                    &mut outputs.v4
+                } else {
+                    &mut outputs.v6
+                };
+                if socket.is_none() {
+                    *socket = Some(bind(if target.is_ipv4() {
+                        "0.0.0.0:0"
+                    } else {
+                        "[::]:0"
+                    })?);
+                }
+                Ok(target)
+            });
+            if let Err(e) = &target {
+                errors.push(e.clone());
+            }
+            outputs.targets.push((destination.name.clone(), target));
+        }
+        (outputs, errors)
+    }
+
+    /// Send one message to the destination of that name, returns where it went
+    pub fn send(&self, name: &str, message: &OscMessage) -> Result<SocketAddr, String> {
+        let target = self
+            .targets
+            .iter()
+            .find(|(n, _)| n == name)
+            .ok_or_else(|| format!("Unknown OSC destination {}", name))?
+            .1
+            .clone()?;
+        let socket = if target.is_ipv4() { &self.v4 } else { &self.v6 };
+        let socket = socket
+            .as_ref()
+            .ok_or_else(|| format!("No OSC socket for {}", name))?;
+        let packet = rosc::encoder::encode(&OscPacket::Message(message.clone()))
+            .map_err(|e| format!("Cannot encode {} : {}", message.addr, e))?;
+        socket
+            .send_to(&packet, target)
+            .map(|_| target)
+            .map_err(|e| format!("Cannot send {} to {} : {}", message.addr, name, e))
+    }
+}
+
+/// An OSC message a cue sends when it fires, written as DESTINATION /address ARGS
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueMessage {
+    pub destination: String,
+    pub message: OscMessage,
+}
+
+/// Typed argument as written in the cue messages and the OSC monitor, like i:12 or s:"Intro"
+pub fn argument(arg: &OscType) -> String {
+    match arg {
+        OscType::Int(i) => format!("i:{}", i),
+        OscType::Float(f) => format!("f:{}", f),
+        OscType::String(s) => format!("s:\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        OscType::Bool(b) => if *b { "T" } else { "F" }.to_owned(),
+        OscType::Long(l) => format!("h:{}", l),
+        OscType::Double(d) => format!("d:{}", d),
+        OscType::Nil => "N".to_owned(),
+        OscType::Inf => "I".to_owned(),
+        OscType::Blob(b) => format!("b:{} bytes", b.len()),
+        other => format!("{:?}", other),
+    }
+}
+
+/// A quoted word loses its quotes and escapes
+fn unquote(word: &str) -> String {
+    match word.strip_prefix('"').and_then(|w| w.strip_suffix('"')) {
+        Some(inner) => {
+            let mut text = String::new();
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                text.push(if c == '\\' {
+                    chars.next().unwrap_or(c)
+                } else {
+                    c
+                });
+            }
+            text
+        }
+        None => word.to_owned(),
+    }
+}
+
+/// i:, f:, s:, h: and d: give the type, T, F, N and I stand alone. Without a type a whole number
+/// is an Int, a decimal one a Float and anything else a String
+pub fn parse_argument(word: &str) -> Result<OscType, String> {
+    let number = |value: &str| format!("{} is not a number", value);
+    let arg = match word.split_once(':') {
+        Some(("i", value)) => OscType::Int(value.parse().map_err(|_| number(value))?),
+        Some(("f", value)) => OscType::Float(value.parse().map_err(|_| number(value))?),
+        Some(("h", value)) => OscType::Long(value.parse().map_err(|_| number(value))?),
+        Some(("d", value)) => OscType::Double(value.parse().map_err(|_| number(value))?),
+        Some(("s", value)) => OscType::String(unquote(value)),
+        _ => match word {
+            "T" => OscType::Bool(true),
+            "F" => OscType::Bool(false),
+            "N" => OscType::Nil,
+            "I" => OscType::Inf,
+            word => match (word.parse::<i32>(), word.parse::<f32>()) {
+                (Ok(i), _) => OscType::Int(i),
+                (_, Ok(f)) => OscType::Float(f),
+                _ => OscType::String(unquote(word)),
+            },
+        },
+    };
+    Ok(arg)
+}
+
+/// Words split on whitespace, quoted text is one word. `;` between messages is a word of its own
+fn words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.push(c);
+            }
+            '\\' if quoted => {
+                word.push(c);
+                word.extend(chars.next());
+            }
+            ';' if !quoted => {
+                words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+                words.push(";".to_owned());
+            }
+            c if c.is_whitespace() && !quoted => {
+                words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            }
+            c => word.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("Missing closing quote in {}", text));
+    }
+    words.extend((!word.is_empty()).then_some(word));
+    Ok(words)
+}
+
+impl CueMessage {
+    fn from_words(words: &[String]) -> Result<Self, String> {
+        let example = "ex : video /clip/3/start i:1 s:\"Intro\"";
+        let [destination, addr, args @ ..] = words else {
+            return Err(format!(
+                "A message is DESTINATION /address ARGS, {}",
+                example
+            ));
+        };
+        if !addr.starts_with('/') {
+            return Err(format!("{} is not an OSC address, {}", addr, example));
+        }
+        Ok(CueMessage {
+            destination: destination.clone(),
+            message: OscMessage {
+                addr: addr.clone(),
+                args: args
+                    .iter()
+                    .map(|a| parse_argument(a))
+                    .collect::<Result<_, _>>()?,
+            },
+        })
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::from_words(&words(text)?)
+    }
+
+    /// Messages separated by `;`, an empty text is no message
+    pub fn parse_list(text: &str) -> Result<Vec<Self>, String> {
+        words(text)?
+            .split(|w| w == ";")
+            .filter(|message| !message.is_empty())
+            .map(Self::from_words)
+            .collect()
+    }
+
+    /// Send to the destination of that name
+    pub fn send(&self, outputs: &Outputs) -> Result<SocketAddr, String> {
+        outputs.send(&self.destination, &self.message)
+    }
+}
+
+impl fmt::Display for CueMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.destination, self.message.addr)?;
+        for arg in &self.message.args {
+            write!(f, " {}", argument(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Send the messages of a cue, every one is tried and the errors are joined
+pub fn fire(outputs: &Outputs, messages: &[CueMessage]) -> Result<(), String> {
+    let errors: Vec<String> = messages
+        .iter()
+        .filter_map(|m| m.send(outputs).err())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join(" | "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn cue_messages_parse_and_reach_their_destination() {
+        let messages = CueMessage::parse_list(
+            "video /clip/3/start i:1 0.5 s:\"Intro; \\\"A\\\"\" T;lights /go",
+        )
+        .unwrap();
+        assert_eq!(
+            messages[0].message.args,
+            [
+                OscType::Int(1),
+                OscType::Float(0.5),
+                OscType::String("Intro; \"A\"".to_owned()),
+                OscType::Bool(true),
+            ]
+        );
+        assert_eq!(
+            messages[0].to_string(),
+            "video /clip/3/start i:1 f:0.5 s:\"Intro; \\\"A\\\"\" T"
+        );
+        assert_eq!(
+            CueMessage::parse(&messages[0].to_string()),
+            Ok(messages[0].clone())
+        );
+        assert_eq!(messages[1].to_string(), "lights /go");
+        assert!(CueMessage::parse_list("  ").unwrap().is_empty());
+        for text in [
+            "video",
+            "video clip",
+            "video /clip i:x",
+            "video /clip s:\"open",
+        ] {
+            assert!(CueMessage::parse(text).is_err(), "{}", text);
+        }
+
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let (outputs, errors) = Outputs::resolve(&[
+            Destination {
+                name: "video".to_owned(),
+                address: server.local_addr().unwrap().to_string(),
+            },
+            Destination {
+                name: "audio".to_owned(),
+                address: "no port".to_owned(),
+            },
+        ]);
+        assert_eq!(errors, ["Cannot resolve no port of audio"]);
+        assert_eq!(
+            fire(&outputs, &messages),
+            Err("Unknown OSC destination lights".to_owned())
+        );
+        assert_eq!(
+            CueMessage::parse("audio /go").unwrap().send(&outputs),
+            Err("Cannot resolve no port of audio".to_owned())
+        );
+        let mut buf = [0u8; rosc::decoder::MTU];
+        let size = server.recv(&mut buf).unwrap();
+        assert_eq!(
+            rosc::decoder::decode_udp(&buf[..size]).unwrap().1,
+            OscPacket::Message(messages[0].message.clone())
+        );
+    }
+}
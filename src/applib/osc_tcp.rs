@@ -0,0 +1,206 @@
+use super::component::OscReceived;
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// SLIP bytes of RFC 1055, used to frame OSC 1.1 packets over a stream
+const END: u8 = 0xC0;
+const ESC: u8 = 0xDB;
+const ESC_END: u8 = 0xDC;
+const ESC_ESC: u8 = 0xDD;
+
+/// A client that does not read its feedback in time is dropped instead of blocking the app
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often the listener looks at its stop flag between two connections
+pub const POLL: Duration = Duration::from_millis(50);
+
+/// Connections of the TCP clients by peer address, the feedback is written back to each of them
+pub type TcpClients = Arc<Mutex<Vec<(SocketAddr, TcpStream)>>>;
+
+/// Frame a packet between two END bytes, the leading one flushes any noise on the line
+pub fn slip_encode(packet: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(packet.len() + 2);
+    frame.push(END);
+    for byte in packet {
+        match *byte {
+            END => frame.extend([ESC, ESC_END]),
+            ESC => frame.extend([ESC, ESC_ESC]),
+            byte => frame.push(byte),
+        }
+    }
+    frame.push(END);
+    frame
+}
+
+/// Rebuilds the frames from the bytes of a stream, in whatever pieces they arrive
+#[derive(Debug, Default)]
+pub struct SlipDecoder {
+    frame: Vec<u8>,
+    escaped: bool,
+}
+
+impl SlipDecoder {
+    /// Frames completed by these bytes, empty frames between two END are skipped
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for byte in bytes {
+            match (self.escaped, *byte) {
+                (false, END) => {
+                    if !self.frame.is_empty() {
+                        frames.push(std::mem::take(&mut self.frame));
+                    }
+                }
+                (false, ESC) => self.escaped = true,
+                (false, byte) => self.frame.push(byte),
+                (true, byte) => {
+                    self.escaped = false;
+                    self.frame.push(match byte {
+                        ESC_END => END,
+                        ESC_ESC => ESC,
+                        // Not a valid escape, kept as is
+                        byte => byte,
+                    });
+                }
+            }
+        }
+        frames
+    }
+}
+
+/// Accept the TCP clients of the listener until `stop` is set, each connection has its own thread
+/// feeding the packets to `sender`. Once stopped the listener is closed and the connections are
+/// shut down
+pub fn serve(
+    listener: TcpListener,
+    sender: Sender<OscReceived>,
+    clients: TcpClients,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    let _ = listener.set_nonblocking(true);
+    thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            let (stream, from) = match listener.accept() {
+                Ok(accepted) => accepted,
+                // Nobody is connecting
+                Err(_) => {
+                    thread::sleep(POLL);
+                    continue;
+                }
+            };
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+            if let (Ok(writer), Ok(mut clients)) = (stream.try_clone(), clients.lock()) {
+                clients.push((from, writer));
+            }
+            let sender = sender.clone();
+            let clients = Arc::clone(&clients);
+            thread::spawn(move || {
+                read_client(stream, from, &sender);
+                // The connection is over, stop writing feedback to it
+                if let Ok(mut clients) = clients.lock() {
+                    clients.retain(|(peer, _)| *peer != from);
+                }
+            });
+        }
+        if let Ok(mut clients) = clients.lock() {
+            for (_, client) in clients.drain(..) {
+                let _ = client.shutdown(Shutdown::Both);
+            }
+        }
+    })
+}
+
+fn read_client(mut stream: TcpStream, from: SocketAddr, sender: &Sender<OscReceived>) {
+    let mut decoder = SlipDecoder::default();
+    let mut buf = [0u8; rosc::decoder::MTU];
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(size) => {
+                for frame in decoder.push(&buf[..size]) {
+                    if let Ok((_, packet)) = rosc::decoder::decode_udp(&frame) {
+                        if sender.send(OscReceived { from, packet }).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Peer address of every connected client
+pub fn peers(clients: &TcpClients) -> Vec<SocketAddr> {
+    clients
+        .lock()
+        .map(|clients| clients.iter().map(|(peer, _)| *peer).collect())
+        .unwrap_or_default()
+}
+
+/// Write the packets to the client connected from `peer`, it is dropped if that fails
+pub fn send(clients: &TcpClients, peer: SocketAddr, packets: &[Vec<u8>]) {
+    let frames: Vec<u8> = packets.iter().flat_map(|p| slip_encode(p)).collect();
+    if let Ok(mut clients) = clients.lock() {
+        clients.retain_mut(|(from, client)| *from != peer || client.write_all(&frames).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rosc::{OscMessage, OscPacket, OscType};
+    use std::sync::mpsc;
+
+    #[test]
+    fn slip_frames_go_both_ways_over_tcp() {
+        let packet = rosc::encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/Utilscord/DMXChan/1".to_owned(),
+            // Bytes that need escaping
+            args: vec![OscType::Int(0xC0DB)],
+        }))
+        .unwrap();
+        let frame = slip_encode(&packet);
+        let mut decoder = SlipDecoder::default();
+        let (first, last) = frame.split_at(7);
+        assert!(decoder.push(first).is_empty());
+        assert_eq!(decoder.push(last), std::slice::from_ref(&packet));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let clients = TcpClients::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let server = serve(listener, sender, Arc::clone(&clients), Arc::clone(&stop));
+        let mut controller = TcpStream::connect(addr).unwrap();
+        let mut other = TcpStream::connect(addr).unwrap();
+        controller.write_all(&frame).unwrap();
+        let received = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(received.from, controller.local_addr().unwrap());
+        assert_eq!(rosc::encoder::encode(&received.packet).unwrap(), packet);
+
+        send(&clients, received.from, std::slice::from_ref(&packet));
+        controller
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let mut buf = vec![0u8; frame.len()];
+        controller.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, frame);
+        // Only the client it was sent to gets it
+        other
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        assert!(other.read(&mut buf).is_err());
+
+        // Stopped, the address can be listened to again and the clients are disconnected
+        stop.store(true, Ordering::Relaxed);
+        server.join().unwrap();
+        assert!(TcpListener::bind(addr).is_ok());
+        assert_eq!(controller.read(&mut buf).unwrap(), 0);
+    }
+}
@@ -1,31 +1,84 @@
 use crate::interact_mod::component::SoundItem;
+use crate::interact_mod::component::Bus;
+use crate::interact_mod::component::format_duration;
+use crate::interact_mod::component::LAYER_INPUTS_START;
 use crate::interact_mod::*;
 use crate::interact_mod::component::DMXInput;
+use crate::interact_mod::learn::Learn;
+use crate::interact_mod::monitor::Monitor;
 use component::OscInfoWidget;
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
 impl TabManager {
     pub fn draw(&mut self, frame : &mut Frame, file_explorer : &mut ratatui_explorer::FileExplorer) {
+        if self.browsing_show {
+            frame.render_widget(&file_explorer.widget(), frame.area());
+            let top_line = Rect { height: 1, ..frame.area() };
+            frame.render_widget(Line::from(" Open show - <Enter> to load, <Esc> to cancel ").black().on_cyan().right_aligned(), top_line);
+            return
+        }
         if let Content::MainMenu(_, input) = &mut self.get_selected_tab_mut().content {
             if input.input_mode {
                 frame.render_widget(&file_explorer.widget(), frame.area());
                 return
             } 
         }
-        frame.render_stateful_widget(
-            self.get_selected_tab_mut().clone(),
-            frame.area(),
-            &mut self.get_selected_tab_mut().content
-        )
-            
+        let area = frame.area();
+        self.get_selected_tab_mut().render(area, frame.buffer_mut());
+        let top_line = Rect { height: 1, ..frame.area() };
+        if self.panic {
+            frame.render_widget(Line::from(" PANIC - <Ctrl> + <R> to clear ").white().on_red().bold().right_aligned(), top_line);
+        } else if self.paused_all {
+            frame.render_widget(Line::from(" PAUSED - <Ctrl> + <P> to resume ").black().on_yellow().right_aligned(), top_line);
+        } else if let Some(message) = self.history.message() {
+            frame.render_widget(Line::from(format!(" {} ", message)).black().on_white().left_aligned(), top_line);
+        }
+        if self.autosave.offer_restore {
+            let [_, popup, _] = Layout::vertical([Constraint::Fill(1), Constraint::Length(4), Constraint::Fill(1)]).areas(frame.area());
+            let [_, popup, _] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(60), Constraint::Fill(1)]).areas(popup);
+            frame.render_widget(Clear, popup);
+            Paragraph::new(vec![
+                Line::from("The last session did not end cleanly."),
+                Line::from("Restore it ? <Y> Restore | <N> Discard"),
+            ]).block(Block::bordered().title("Recovery").yellow()).centered().render(popup, frame.buffer_mut());
+        }
+        if self.show_help {
+            self.draw_help(frame);
+        }
+    }
+
+    /// Keys of the active keymap, one section per context on two columns
+    fn draw_help(&self, frame : &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(Clear, area);
+        let block = Block::bordered().title(" Keys - <F1> or <Esc> to close ").title_alignment(Alignment::Center).cyan();
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let [errors_area, tables_area] = Layout::vertical([Constraint::Length(self.keymap.errors.len() as u16), Constraint::Fill(1)]).areas(inner);
+        Paragraph::new(self.keymap.errors.iter().map(|e| Line::from(e.as_str()).red()).collect::<Vec<_>>()).render(errors_area, frame.buffer_mut());
+
+        let mut rows = Vec::new();
+        let mut context = "";
+        for (name, keys, description) in self.keymap.help() {
+            if name != context {
+                context = name;
+                rows.push(Row::new(vec![Cell::from(name.to_owned()).bold().yellow()]));
+            }
+            rows.push(Row::new(vec![Cell::from(keys), Cell::from(description)]).white());
+        }
+        let right = rows.split_off(rows.len().div_ceil(2));
+        let columns = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).split(tables_area);
+        for (rows, area) in [rows, right].into_iter().zip(columns.iter()) {
+            Widget::render(Table::new(rows, [Constraint::Length(24), Constraint::Fill(1)]), *area, frame.buffer_mut());
+        }
     }
 }
-impl StatefulWidget for Tab {
-    type State = Content;
-    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+impl Tab {
+    /// Drawn in place, a copy of the tab every frame would copy every message of the monitor
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let main_tab_border = Block::bordered()
-            .title_top(state.to_string())
+            .title_top(self.content.to_string())
             .fg(Color::White)
             .title_bottom(
                 match &self.content {
@@ -56,6 +109,9 @@ impl StatefulWidget for Tab {
                     Content::Dmx(..) => {
                         if self.is_used() {"||"} else {"| Press <Shift> + ◄ ► to change Tab | Press ◄ ► to navigate between DMX Channel | "}
                     }
+                    Content::Monitor(_) => {
+                        if self.is_used() {"| Press <Enter> to confirm the filter | <Esc> to clear it |"} else {"| Press <Shift> + ◄ ► to change Tab |"}
+                    }
                 }
             )
             .title_alignment(Alignment::Center)
@@ -79,7 +135,10 @@ impl StatefulWidget for Tab {
                             } else {"| Press <Shift> + ▲ ▼ to navigate |"}
                         },
                         Content::Dmx(..) => {
-                            if self.is_used() {""} else {"| Enter <0-9> to set to DMX value | Press <Backspace> to reset DMX Value | <CTRL> + ▲ ▼ to modify DMX Value by 10 | ▲ ▼ to modify DMX Value by 1 |"}
+                            if self.is_used() {""} else {"| Enter <0-9> to set to DMX value | Press <Backspace> to reset DMX Value | <Shift> + <S> Store Safe Look | <CTRL> + ▲ ▼ to modify DMX Value by 10 | ▲ ▼ to modify DMX Value by 1 |"}
+                        }
+                        Content::Monitor(_) => {
+                            if self.is_used() {""} else {"| <Space> Pause | </> Filter | <E> Export | <C> Clear | ▲ ▼ <PageUp> <PageDown> <Home> <End> to scroll |"}
                         }
                         
                     }
@@ -90,7 +149,7 @@ impl StatefulWidget for Tab {
         let tab_content = main_tab_border.inner(area);
         main_tab_border.render(area, buf);
 
-        match state {
+        match &mut self.content {
             Content::MainMenu(sound_list, input) => {
                 let vert = Layout::vertical([Constraint::Length(3), Constraint::Fill(3)]);
 
@@ -109,18 +168,24 @@ impl StatefulWidget for Tab {
                                 .edit_tab_selected,
                         );
                 } else {
+                    let hor = Layout::horizontal([Constraint::Fill(1), Constraint::Length(8 * sound_list.buses.len() as u16)]);
+                    let [list_area, bus_area] = hor.areas(tab_footer);
                     sound_list
                         .clone()
-                        .render(tab_footer, buf, &mut sound_list.state);
+                        .render(list_area, buf, &mut sound_list.state);
+                    let focused_bus = sound_list.state.selected().and_then(|i| sound_list.sound_files.get(i)).map(|si| si.bus);
+                    let bus_block = Block::bordered().title_top("Buses").title_alignment(Alignment::Center).title_bottom("| <B> Route | <Ctrl> + ▲ ▼ | <M> Mute | <S> Solo |");
+                    let bus_areas = Layout::horizontal(vec![Constraint::Fill(1); sound_list.buses.len()]).split(bus_block.inner(bus_area));
+                    bus_block.render(bus_area, buf);
+                    for (id, bus) in sound_list.buses.iter().enumerate() {
+                        let mut focused = focused_bus == Some(id);
+                        bus.clone().render(bus_areas[id], buf, &mut focused);
+                    }
                 }
             }
             Content::Osc(listening_ip_input, ) => {
-                match [listening_ip_input.focus].iter().find(|b| **b) {
-                    Some(_) => {},
-                    None => {listening_ip_input.focus = true},
-                }
                 let vert = Layout::vertical([Constraint::Fill(1), Constraint::Fill(3)]);
-                let [tab_content, _tab_footer] = vert.areas(tab_content);
+                let [tab_content, tab_footer] = vert.areas(tab_content);
                 let ip_input_areas =
                     Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]);
                 let [listening_input_area, info_area] = ip_input_areas.areas(tab_content);
@@ -130,7 +195,17 @@ impl StatefulWidget for Tab {
                     &mut listening_ip_input.input,
                 );
                 listening_ip_input.last_action_widget.clone().render(info_area, buf);
-                
+                let [learn_area, destinations_area] = Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(tab_footer);
+                listening_ip_input.learn.clone().render(learn_area, buf, &mut !listening_ip_input.focus);
+                let [list_area, test_area] = match listening_ip_input.test_message {
+                    Some(_) => Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(destinations_area),
+                    None => [destinations_area, Rect::default()],
+                };
+                Widget::render(List::new(listening_ip_input.destinations.iter().map(|d| ListItem::new(format!("{} -> {}", d.name, d.address))))
+                    .block(Block::bordered().title("OSC Destinations").title_alignment(Alignment::Center).title_bottom(if listening_ip_input.focus {""} else {"| <T> Test |"})), list_area, buf);
+                if let Some(text) = &listening_ip_input.test_message {
+                    Paragraph::new(text.clone()).block(Block::bordered().title("Send : DESTINATION /address ARGS").title_bottom("| <Enter> Send | <Esc> Close |").title_alignment(Alignment::Center).fg(Color::LightGreen)).render(test_area, buf);
+                }
             },
             Content::Dmx(dimmer_input,r_input,v_input,b_input,adr,ip, dmx_status) => {
                 match [dimmer_input.is_focused,r_input.is_focused,v_input.is_focused,b_input.is_focused].iter().find(|b| **b) {
@@ -154,6 +229,7 @@ impl StatefulWidget for Tab {
                 b_input.clone().render(right,buf ,&mut b_input.value );
                 
             }
+            Content::Monitor(monitor) => monitor.render(tab_content, buf),
         }
     }
 }
@@ -172,6 +248,9 @@ impl StatefulWidget for Input {
         .block(
             Block::bordered()
                 .title(match self.input_mode {
+                    false if self.edited => Line::from(format!("{} - Suggested, <Enter> to confirm", state))
+                        .centered()
+                        .fg(Color::Cyan),
                     false => Line::from(state.to_string()).centered().fg(Color::White),
                     true => Line::from(format!("{} - Edit", state))
                         .centered()
@@ -200,12 +279,82 @@ impl StatefulWidget for IPInput {
                         self.title.to_string()
                     })
                     .title_alignment(Alignment::Center)
-                    .title_position(block::Position::Top),
+                    .title_position(block::Position::Top)
+                    .border_style(Style::default().fg(if self.focus {Color::Yellow} else {Color::White})),
             )
             .render(area, buf);
     }
 }
 
+impl StatefulWidget for Learn {
+    /// The mapping list is focused
+    type State = bool;
+    fn render(mut self, area: Rect, buf: &mut Buffer, focused: &mut bool) {
+        let color = if *focused {Color::Yellow} else {Color::White};
+        let title = match &self.waiting {
+            Some(action) => format!("Mappings - Waiting for the OSC message of {}", action),
+            None => "Mappings".to_string(),
+        };
+        let [mut list_area, picker_area] = match self.picking {
+            Some(_) => Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area),
+            None => [area, Rect::default()],
+        };
+        if let Some(text) = &self.editing {
+            let [list, edit_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(list_area);
+            Paragraph::new(text.clone()).block(Block::bordered().title("Edit : ADDRESS -> ACTION FROM => TO").title_bottom("| <Enter> Save | <Esc> Cancel |").title_alignment(Alignment::Center).fg(Color::LightGreen)).render(edit_area, buf);
+            list_area = list;
+        }
+        let mappings = List::new(self.mappings.iter().map(|m| ListItem::new(m.to_string())))
+            .block(Block::bordered().title(title).title_alignment(Alignment::Center).fg(color)
+                .title_bottom(if *focused {"| <L> Learn | <Enter> Edit | <Delete> Delete | <Esc> Cancel learning | <Shift> + ▲ Listening address |"} else {"| <Shift> + ▼ Mappings |"}))
+            .highlight_style(Style::default().bg(Color::White).fg(Color::Black))
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(mappings, list_area, buf, &mut self.state);
+        if let Some(filter) = &self.picking {
+            let actions = List::new(self.choices().into_iter().map(ListItem::new))
+                .block(Block::bordered().title(format!("Action : {}", filter)).title_bottom("| Type to filter | <Enter> Pick | <Esc> Cancel |").title_alignment(Alignment::Center).fg(Color::LightGreen))
+                .highlight_style(Style::default().bg(Color::White).fg(Color::Black))
+                .highlight_spacing(HighlightSpacing::Always);
+            StatefulWidget::render(actions, picker_area, buf, &mut self.picker_state.clone());
+        }
+    }
+}
+
+impl Monitor {
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let filtered = self.filtered();
+        let count = filtered.len();
+        let mut title = format!("{} messages", count);
+        if self.paused {
+            title += &format!(" - Paused ({} held)", self.held());
+        }
+        if self.filtering || !self.filter.is_empty() {
+            title += &format!(" - Filter : {}", self.filter);
+        }
+        let color = if self.filtering {Color::LightGreen} else if self.paused {Color::Yellow} else {Color::White};
+        let block = Block::bordered().title(title).title_alignment(Alignment::Center).fg(color).title_bottom(Line::from(self.status.clone()).left_aligned());
+        // Without a selection the newest messages are shown, with one the window follows it
+        let visible = (block.inner(area).height.saturating_sub(1) as usize).max(1);
+        let selected = self.state.selected().map(|s| s.min(count.saturating_sub(1)));
+        let offset = match selected {
+            None => count.saturating_sub(visible),
+            Some(selected) => self.state.offset().clamp((selected + 1).saturating_sub(visible), selected),
+        };
+        // Only the lines on screen are built, the log holds thousands
+        let rows: Vec<Row> = filtered.iter().skip(offset).take(visible).map(|e| {
+            let row = Row::new(vec![monitor::clock(e.time), e.source(), e.address.clone(), e.arguments(), e.outcome()]);
+            if e.handled.is_err() {row.red()} else {row.white()}
+        }).collect();
+        *self.state.offset_mut() = offset;
+        let mut window = TableState::default().with_selected(selected.map(|s| s - offset));
+        let table = Table::new(rows, [Constraint::Length(12), Constraint::Length(22), Constraint::Fill(2), Constraint::Fill(1), Constraint::Fill(2)])
+            .header(Row::new(["Time", "Source", "Address", "Arguments", "Handled"]).bold().yellow())
+            .block(block)
+            .row_highlight_style(Style::default().bg(Color::White).fg(Color::Black));
+        StatefulWidget::render(table, area, buf, &mut window);
+    }
+}
+
 impl StatefulWidget for SoundItem {
     type State = usize;
     fn render(self, area: Rect, buf: &mut Buffer, _state: &mut usize) {
@@ -221,11 +370,17 @@ impl StatefulWidget for SoundItem {
         let content = popup.inner(area);
         popup.render(area, buf);
         let layout = Layout::vertical([
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(50),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3 * self.layers.len() as u16),
+            Constraint::Fill(1),
         ]);
-        let [fade_in_area, fade_out_area, trim_in_area] = layout.areas(content);
+        let [fade_in_area, fade_out_area, trim_in_area, trim_out_area, marker_input_area, notes_area, osc_area, layers_area, marker_list_area] = layout.areas(content);
 
         let mut copy = self.fade_tab_content.clone();
 
@@ -238,6 +393,45 @@ impl StatefulWidget for SoundItem {
         copy[2]
             .clone()
             .render(trim_in_area, buf, &mut copy[2].input_field_title);
+        copy[3]
+            .clone()
+            .render(trim_out_area, buf, &mut copy[3].input_field_title);
+        copy[4]
+            .clone()
+            .render(marker_input_area, buf, &mut copy[4].input_field_title);
+        copy[5]
+            .clone()
+            .render(notes_area, buf, &mut copy[5].input_field_title);
+        copy[6]
+            .clone()
+            .render(osc_area, buf, &mut copy[6].input_field_title);
+
+        // One row per layer : Level | Offset
+        let layer_rows = Layout::vertical(vec![Constraint::Length(3); self.layers.len()]).split(layers_area);
+        for (layer, row) in layer_rows.iter().enumerate() {
+            let [level_area, offset_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(*row);
+            let level = LAYER_INPUTS_START + layer * 2;
+            copy[level]
+                .clone()
+                .render(level_area, buf, &mut copy[level].input_field_title);
+            copy[level + 1]
+                .clone()
+                .render(offset_area, buf, &mut copy[level + 1].input_field_title);
+        }
+
+        let markers = List::new(self.markers.iter().map(|m| {
+            ListItem::new(format!("{} @ {:.1}s ({})", m.name, m.position.as_secs_f32(), format_duration(m.position)))
+        }))
+        .block(
+            Block::bordered()
+                .title("Markers")
+                .title_alignment(Alignment::Center)
+                .title_bottom(if self.fade_tab_content[4].is_selected {"| ◄ ► Select | <P> Play from Marker | <Del> Remove |"} else {""})
+                .fg(if self.fade_tab_content[4].is_selected {Color::Yellow} else {Color::White}),
+        )
+        .highlight_style(Style::default().bg(Color::White).fg(Color::Black));
+        let mut marker_state = ListState::default().with_selected(if self.fade_tab_content[4].is_selected && !self.markers.is_empty() {Some(self.selected_marker)} else {None});
+        StatefulWidget::render(markers, marker_list_area, buf, &mut marker_state);
     }
 }
 
@@ -268,22 +462,35 @@ impl StatefulWidget for SoundList {
             })
             .title_bottom(
             match state.selected() {
-                    Some(_) => {"| <Enter> Play | <Space> Pause | <Backspace> Remove | <Shift> + ▲ ▼ Local Volume | +/- General Volume |"},
+                    Some(_) => {"| <Enter> Play | <Space> Pause | <Backspace> Remove | ◄ ► Seek 5s (<Shift> 1s) | <R> Restart | [ ] Markers | <L> Pick Layer | <Shift> + <L> Layered Cue | <Shift> + <P> Playlist | <N> Skip | <E> End Fade | <W> Bounce WAV (<Shift> FLAC) | <A> Suggest Trim (<Shift> All) | < > Silence Threshold | <Shift> + ▲ ▼ Local Volume | +/- General Volume |"},
                     None => {""}
                 }
             ).title_alignment(Alignment::Center)
             .title_top(
                     match state.selected() {
-                        Some(_) => {format!("{}{}",if self.currently_playing.is_empty() {""} else {"Playing : "},self.currently_playing.clone())}
+                        Some(_) => {format!("{}{}{}",if self.currently_playing.is_empty() {""} else {"Playing : "},self.currently_playing.clone(),
+                            match self.playing_position() {
+                                Some((elapsed, total)) => format!(" [{} / {}]", format_duration(elapsed), format_duration(total)),
+                                None => String::new(),
+                            })}
                         None => {"-".to_string()}
                     }
             ).title_alignment(Alignment::Right)
             .title_bottom(
                 match state.selected() {
-                    Some(_) => {format!("|General Volume : {:.2}|", self.volume)}
+                    Some(_) => {
+                        let now_playing = self.playlist_now_playing.lock().map(|n| n.clone()).unwrap_or_default();
+                        format!("|Playlist {} : {}{} | XF {:.0}s |General Volume : {:.2}|",
+                            self.playlist_group_name(),
+                            if now_playing.is_empty() {"Stopped"} else {now_playing.as_str()},
+                            if self.playlist.shuffle {" | Shuffle"} else {""},
+                            self.playlist.crossfade.as_secs_f32(),
+                            self.volume)
+                    }
                     None => {"".to_string()}
                 }
             ).title_alignment(Alignment::Right)
+            .title_top(Line::from(self.status.clone()).left_aligned())
             )
             .highlight_style(Style::default().bg(Color::White).fg(Color::Black))
             .highlight_spacing(HighlightSpacing::Always);
@@ -314,6 +521,32 @@ impl StatefulWidget for DMXInput {
             .bar_width(frame.inner(area).width)
             .render(frame.inner(area),buf );
         frame.render(area,buf );
+    }}
+
+impl StatefulWidget for Bus {
+    type State = bool;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let color = if *state {Color::Yellow} else {Color::White};
+        let frame = Block::bordered()
+            .title(self.name.clone())
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(vec![
+                Span::styled("M", Style::new().fg(if self.mute {Color::LightRed} else {Color::DarkGray})),
+                Span::raw(" "),
+                Span::styled("S", Style::new().fg(if self.solo {Color::LightGreen} else {Color::DarkGray})),
+            ]).centered())
+            .style(Style::new().fg(color));
+        let bar = Bar::default()
+            .value((self.volume * 100.0).round() as u64)
+            .text_value(format!("{:.2}", self.volume));
+        BarChart::default()
+            .direction(Direction::Vertical)
+            .max(200)
+            .data(BarGroup::default().bars(&[bar]))
+            .style(Style::new().fg(color))
+            .bar_width(frame.inner(area).width)
+            .render(frame.inner(area), buf);
+        frame.render(area, buf);
     }
 }
 
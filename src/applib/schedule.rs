@@ -0,0 +1,133 @@
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+use std::time::{Duration, SystemTime};
+
+/// Timetag meaning "immediately" in OSC 1.0
+const IMMEDIATELY: OscTime = OscTime {
+    seconds: 0,
+    fractional: 1,
+};
+
+/// Messages of the OSC bundles waiting for their timetag
+#[derive(Debug, Default)]
+pub struct Schedule {
+    /// Time to run and message, in the order they arrived
+    queue: Vec<(SystemTime, OscMessage)>,
+}
+
+impl Schedule {
+    /// Unpack the bundle and its nested bundles in order. Returns the messages to run now,
+    /// the ones with a future timetag are queued
+    pub fn unpack(&mut self, bundle: OscBundle, now: SystemTime) -> Vec<OscMessage> {
+        let mut due = Vec::new();
+        self.unpack_at(bundle, None, now, &mut due);
+        due
+    }
+
+    fn unpack_at(
+        &mut self,
+        bundle: OscBundle,
+        parent: Option<SystemTime>,
+        now: SystemTime,
+        due: &mut Vec<OscMessage>,
+    ) {
+        // A nested bundle cannot run before the bundle holding it
+        let at = if bundle.timetag == IMMEDIATELY {
+            parent
+        } else {
+            let at = SystemTime::from(bundle.timetag);
+            Some(parent.map_or(at, |parent| parent.max(at)))
+        };
+        for packet in bundle.content {
+            match packet {
+                OscPacket::Message(message) => match at {
+                    Some(at) if at > now => self.queue.push((at, message)),
+                    _ => due.push(message),
+                },
+                OscPacket::Bundle(bundle) => self.unpack_at(bundle, at, now, due),
+            }
+        }
+    }
+
+    /// Queued messages whose time has come, by time then in the order they arrived
+    pub fn due(&mut self, now: SystemTime) -> Vec<OscMessage> {
+        let (mut due, queue): (Vec<_>, Vec<_>) =
+            self.queue.drain(..).partition(|(at, _)| *at <= now);
+        self.queue = queue;
+        due.sort_by_key(|(at, _)| *at);
+        due.into_iter().map(|(_, message)| message).collect()
+    }
+
+    /// Time left before the next queued message
+    pub fn next(&self, now: SystemTime) -> Option<Duration> {
+        self.queue
+            .iter()
+            .map(|(at, _)| at.duration_since(now).unwrap_or_default())
+            .min()
+    }
+
+    /// Drop every queued message, returns how many there were
+    pub fn cancel(&mut self) -> usize {
+        let cancelled = self.queue.len();
+        self.queue.clear();
+        cancelled
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(addr: &str) -> OscPacket {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_owned(),
+            args: Vec::new(),
+        })
+    }
+
+    fn addrs(messages: Vec<OscMessage>) -> Vec<String> {
+        messages.into_iter().map(|m| m.addr).collect()
+    }
+
+    #[test]
+    fn bundles_run_in_order_at_their_timetag() {
+        let now = SystemTime::now();
+        let later = |ms| OscTime::try_from(now + Duration::from_millis(ms)).unwrap();
+        let bundle = OscBundle {
+            timetag: IMMEDIATELY,
+            content: vec![
+                message("/Utilscord/Play/0"),
+                OscPacket::Bundle(OscBundle {
+                    timetag: later(500),
+                    content: vec![message("/Utilscord/Stop"), message("/DMXChan/1/0")],
+                }),
+                OscPacket::Bundle(OscBundle {
+                    timetag: later(200),
+                    content: vec![
+                        message("/DMXChan/1/255"),
+                        // Earlier than its parent, runs with it
+                        OscPacket::Bundle(OscBundle {
+                            timetag: later(100),
+                            content: vec![message("/Utilscord/Play/1")],
+                        }),
+                    ],
+                }),
+                message("/Utilscord/Volume"),
+            ],
+        };
+        let mut schedule = Schedule::default();
+        assert_eq!(
+            addrs(schedule.unpack(bundle, now)),
+            ["/Utilscord/Play/0", "/Utilscord/Volume"]
+        );
+        let next = schedule.next(now).unwrap();
+        assert!(next > Duration::from_millis(190) && next <= Duration::from_millis(200));
+        assert!(schedule.due(now + Duration::from_millis(150)).is_empty());
+        assert_eq!(
+            addrs(schedule.due(now + Duration::from_millis(300))),
+            ["/DMXChan/1/255", "/Utilscord/Play/1"]
+        );
+        assert_eq!(schedule.cancel(), 2);
+        assert!(schedule.due(now + Duration::from_secs(1)).is_empty());
+        assert_eq!(schedule.next(now), None);
+    }
+}
@@ -0,0 +1,743 @@
+use super::component::{Bus, Content, Layer, Marker, SoundItem, SoundList};
+use super::learn::{Learn, Mapping};
+use super::osc_address;
+use super::osc_send::{CueMessage, Destination, Outputs};
+use super::TabManager;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::net::ToSocketAddrs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The recovery file is rewritten at least this often
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+/// Shortest time between two writes of the recovery file, edits in between are saved together
+const AUTOSAVE_MIN_GAP: Duration = Duration::from_secs(1);
+
+/// Everything configured in the tabs, saved as TOML next to the show
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Show {
+    pub sounds: SoundsShow,
+    pub dmx: DmxShow,
+    pub osc: OscShow,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct SoundsShow {
+    /// Sound directory, relative to the show file
+    pub directory: String,
+    /// Cue list CSV the items come from, relative to the show file. The directory is not read when set
+    pub cue_list: Option<String>,
+    pub volume: f32,
+    pub silence_threshold: f32,
+    pub pause_fade: f32,
+    pub panic_fade: f32,
+    /// Item in standby, the one selected in the list
+    pub standby: Option<String>,
+    pub buses: Vec<BusShow>,
+    pub playlist: PlaylistShow,
+    pub items: Vec<ItemShow>,
+}
+
+impl Default for SoundsShow {
+    fn default() -> Self {
+        let soundlist = SoundList::from_dir(String::new());
+        Self {
+            directory: String::new(),
+            cue_list: None,
+            volume: soundlist.volume,
+            silence_threshold: soundlist.silence_threshold,
+            pause_fade: soundlist.pause_fade.as_secs_f32(),
+            panic_fade: soundlist.panic_fade.as_secs_f32(),
+            standby: None,
+            buses: Vec::new(),
+            playlist: PlaylistShow::default(),
+            items: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct BusShow {
+    pub name: String,
+    pub volume: f32,
+    pub mute: bool,
+    pub solo: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct PlaylistShow {
+    pub shuffle: bool,
+    pub crossfade: f32,
+    /// Bus name, every item when missing
+    pub group: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ItemShow {
+    /// File name in the sound directory, or the name of a layered cue
+    pub name: String,
+    pub display_name: Option<String>,
+    pub local_volume: f32,
+    pub fade_in: f32,
+    pub fade_out: f32,
+    pub trim_in: f32,
+    pub trim_out: Option<f32>,
+    pub bus: String,
+    pub notes: String,
+    /// OSC messages sent when the cue fires, as DESTINATION /address ARGS
+    pub osc: Vec<String>,
+    pub markers: Vec<MarkerShow>,
+    pub layers: Vec<LayerShow>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct MarkerShow {
+    pub name: String,
+    pub position: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct LayerShow {
+    pub file: String,
+    pub level: f32,
+    pub offset: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct DmxShow {
+    pub serial: String,
+    pub address: usize,
+    pub faders: Vec<u8>,
+    pub safe_look: Vec<SafeLevelShow>,
+}
+
+impl Default for DmxShow {
+    fn default() -> Self {
+        Self {
+            serial: String::new(),
+            address: 1,
+            faders: Vec::new(),
+            safe_look: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct SafeLevelShow {
+    pub channel: usize,
+    pub level: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct OscShow {
+    /// IP:Port the OSC listener is bound to separated by commas, empty when it is not listening
+    pub listen: String,
+    /// First part of every OSC address, the default one when empty
+    pub namespace: String,
+    /// Host:Port the OSC replies go to, empty when there is none
+    pub reply: String,
+    /// Addresses of remote devices mapped to actions
+    pub mappings: Vec<Mapping>,
+    /// Show systems the cues send OSC to
+    pub destinations: Vec<Destination>,
+}
+
+impl Show {
+    /// Read the state of every tab, the sound directory is stored relative to `show_dir`
+    pub fn capture(tab_manager: &TabManager, show_dir: &Path) -> Self {
+        let mut show = Show::default();
+        for tab in &tab_manager.tabs {
+            match &tab.content {
+                Content::MainMenu(soundlist, _input) => {
+                    show.sounds = SoundsShow::capture(soundlist, show_dir)
+                }
+                Content::Osc(ipinput) => {
+                    if tab_manager.osc_receiver.is_some() {
+                        show.osc.listen = ipinput.input.clone();
+                    }
+                    show.osc.mappings = ipinput.learn.mappings.clone();
+                    show.osc.destinations = ipinput.destinations.clone();
+                    if tab_manager.osc_namespace != osc_address::DEFAULT_NAMESPACE {
+                        show.osc.namespace = tab_manager.osc_namespace.clone();
+                    }
+                    if let Some(reply) = tab_manager.osc_reply {
+                        show.osc.reply = reply.to_string();
+                    }
+                }
+                Content::Dmx(f1, f2, f3, f4, adr, serial, _dmx_status) => {
+                    show.dmx = DmxShow {
+                        serial: serial.clone(),
+                        address: **adr,
+                        faders: [f1, f2, f3, f4].iter().map(|f| f.value).collect(),
+                        safe_look: tab_manager
+                            .dmx_handler
+                            .safe_look
+                            .iter()
+                            .map(|(channel, level)| SafeLevelShow {
+                                channel: *channel,
+                                level: *level,
+                            })
+                            .collect(),
+                    }
+                }
+                Content::Monitor(_) => (),
+            }
+        }
+        show
+    }
+
+    /// Replace the state of every tab, returns the problems that did not stop the loading
+    pub fn apply(self, tab_manager: &mut TabManager, show_dir: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut reopen_dmx = false;
+        for tab in &mut tab_manager.tabs {
+            match &mut tab.content {
+                Content::MainMenu(soundlist, input) => {
+                    let selected = soundlist.selected;
+                    *soundlist = self.sounds.build(show_dir, &mut warnings);
+                    // Focus the list when there is an item in standby
+                    soundlist.selected = selected || soundlist.state.selected().is_some();
+                    input.is_selected = !soundlist.selected;
+                    input.input = soundlist.current_dir.clone();
+                }
+                Content::Osc(ipinput) => {
+                    ipinput.learn = Learn {
+                        mappings: self.osc.mappings.clone(),
+                        ..Learn::default()
+                    };
+                    ipinput.destinations.clear();
+                    for destination in &self.osc.destinations {
+                        if destination.name.is_empty() || destination.name.contains(' ') {
+                            warnings.push(format!(
+                                "OSC destination \"{}\" needs a name without spaces",
+                                destination.name
+                            ));
+                        } else if ipinput
+                            .destinations
+                            .iter()
+                            .any(|d| d.name == destination.name)
+                        {
+                            warnings
+                                .push(format!("OSC destination {} is set twice", destination.name));
+                        } else {
+                            ipinput.destinations.push(destination.clone());
+                        }
+                    }
+                    let (outputs, errors) = Outputs::resolve(&ipinput.destinations);
+                    ipinput.outputs = outputs;
+                    warnings.extend(errors);
+                    if !self.osc.listen.is_empty() {
+                        ipinput.input = self.osc.listen.clone();
+                        match ipinput.listen() {
+                            Ok(receiver) => tab_manager.osc_receiver = Some(receiver),
+                            Err(()) => warnings
+                                .push(format!("Cannot listen to OSC on {}", self.osc.listen)),
+                        }
+                    }
+                    if self.osc.namespace.is_empty() {
+                        tab_manager.osc_namespace = osc_address::DEFAULT_NAMESPACE.to_owned();
+                    } else {
+                        match osc_address::check_namespace(&self.osc.namespace) {
+                            Ok(()) => tab_manager.osc_namespace = self.osc.namespace.clone(),
+                            Err(e) => warnings.push(e),
+                        }
+                    }
+                    if !self.osc.reply.is_empty() {
+                        match self.osc.reply.to_socket_addrs().map(|mut a| a.next()) {
+                            Ok(Some(reply)) => tab_manager.osc_reply = Some(reply),
+                            _ => warnings
+                                .push(format!("Cannot send OSC replies to {}", self.osc.reply)),
+                        }
+                    }
+                }
+                Content::Dmx(f1, f2, f3, f4, adr, serial, _dmx_status) => {
+                    if !self.dmx.serial.is_empty() && *serial != self.dmx.serial {
+                        *serial = self.dmx.serial.clone();
+                        reopen_dmx = true;
+                    }
+                    **adr = self.dmx.address.clamp(1, open_dmx::DMX_CHANNELS - 3);
+                    for (fader, value) in [f1, f2, f3, f4].into_iter().zip(&self.dmx.faders) {
+                        fader.value = *value;
+                    }
+                    tab_manager.dmx_handler.safe_look = self
+                        .dmx
+                        .safe_look
+                        .iter()
+                        .map(|s| (s.channel, s.level))
+                        .collect();
+                }
+                Content::Monitor(_) => (),
+            }
+        }
+        if reopen_dmx {
+            tab_manager.reopen_dmx();
+        }
+        tab_manager
+            .dmx_handler
+            .update_dmx(&mut tab_manager.tabs[2].content);
+        warnings
+    }
+}
+
+impl SoundsShow {
+    fn capture(soundlist: &SoundList, show_dir: &Path) -> Self {
+        Self {
+            directory: relative_path(Path::new(&soundlist.current_dir), show_dir),
+            cue_list: soundlist
+                .cue_list
+                .as_ref()
+                .map(|path| relative_path(path, show_dir)),
+            volume: soundlist.volume,
+            silence_threshold: soundlist.silence_threshold,
+            pause_fade: soundlist.pause_fade.as_secs_f32(),
+            panic_fade: soundlist.panic_fade.as_secs_f32(),
+            standby: soundlist
+                .state
+                .selected()
+                .and_then(|index| soundlist.sound_files.get(index))
+                .map(|si| si.name.clone()),
+            buses: soundlist
+                .buses
+                .iter()
+                .map(|b| BusShow {
+                    name: b.name.clone(),
+                    volume: b.volume,
+                    mute: b.mute,
+                    solo: b.solo,
+                })
+                .collect(),
+            playlist: PlaylistShow {
+                shuffle: soundlist.playlist.shuffle,
+                crossfade: soundlist.playlist.crossfade.as_secs_f32(),
+                group: soundlist
+                    .playlist
+                    .group
+                    .map(|bus| soundlist.bus_name(bus).to_owned()),
+            },
+            items: soundlist
+                .sound_files
+                .iter()
+                .map(|si| ItemShow::capture(si, soundlist))
+                .collect(),
+        }
+    }
+
+    fn build(&self, show_dir: &Path, warnings: &mut Vec<String>) -> SoundList {
+        let directory = if self.directory.is_empty() {
+            String::new()
+        } else {
+            show_dir
+                .join(&self.directory)
+                .to_string_lossy()
+                .into_owned()
+        };
+        if !directory.is_empty() && fs::read_dir(&directory).is_err() {
+            warnings.push(format!("Sound directory {} not found", directory));
+        }
+        let mut soundlist = match &self.cue_list {
+            Some(cue_list) => {
+                let mut soundlist = SoundList::from_dir(String::new());
+                match soundlist.load_cue_list(&show_dir.join(cue_list)) {
+                    Ok(errors) => warnings.extend(errors),
+                    Err(e) => warnings.push(e),
+                }
+                soundlist
+            }
+            None => SoundList::from_dir(directory),
+        };
+        soundlist.volume = self.volume.clamp(0.0, 2.0);
+        soundlist.silence_threshold = self.silence_threshold.clamp(-96.0, 0.0);
+        soundlist.pause_fade = seconds(self.pause_fade);
+        soundlist.panic_fade = seconds(self.panic_fade);
+        if !self.buses.is_empty() {
+            soundlist.buses = self
+                .buses
+                .iter()
+                .map(|b| Bus {
+                    name: b.name.clone(),
+                    volume: b.volume.clamp(0.0, 2.0),
+                    mute: b.mute,
+                    solo: b.solo,
+                })
+                .collect();
+        }
+        soundlist.playlist.shuffle = self.playlist.shuffle;
+        soundlist.playlist.crossfade = seconds(self.playlist.crossfade);
+        soundlist.playlist.group = self
+            .playlist
+            .group
+            .as_ref()
+            .and_then(|name| soundlist.bus_index(name));
+
+        for item in &self.items {
+            if !item.layers.is_empty() {
+                let layers: Vec<Layer> = item
+                    .layers
+                    .iter()
+                    .map(|l| Layer {
+                        file: l.file.clone(),
+                        level: l.level.clamp(0.0, 2.0),
+                        offset: seconds(l.offset),
+                        duration: soundlist
+                            .sound_files
+                            .iter()
+                            .find(|si| si.name == l.file)
+                            .map(|si| si.max_duration)
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+                let max_duration = layers
+                    .iter()
+                    .map(|l| l.duration + l.offset)
+                    .max()
+                    .unwrap_or_default();
+                soundlist.sound_files.push(SoundItem::layered(
+                    item.name.clone(),
+                    layers,
+                    max_duration,
+                ));
+            }
+            let bus = soundlist.bus_index(&item.bus).unwrap_or(0);
+            match soundlist
+                .sound_files
+                .iter_mut()
+                .find(|si| si.name == item.name)
+            {
+                Some(si) => item.apply(si, bus, warnings),
+                None => warnings.push(format!("{} not found in the sound directory", item.name)),
+            }
+        }
+        if let Some(standby) = &self.standby {
+            let index = soundlist
+                .sound_files
+                .iter()
+                .position(|si| &si.name == standby);
+            soundlist.state.select(index);
+        }
+        soundlist
+    }
+}
+
+impl ItemShow {
+    fn capture(si: &SoundItem, soundlist: &SoundList) -> Self {
+        // The inputs and not `SoundItem::fades`, which plays whole seconds
+        let fade = |input: usize| match si.fade_tab_content[input].input.trim().parse::<f32>() {
+            Ok(x) if x.is_finite() && x > 0.0 => x,
+            _ => 0.0,
+        };
+        Self {
+            name: si.name.clone(),
+            display_name: si.display_name.clone(),
+            local_volume: si.local_volume,
+            fade_in: fade(0),
+            fade_out: fade(1),
+            trim_in: si.trim_in.as_secs_f32(),
+            trim_out: si.trim_out.map(|d| d.as_secs_f32()),
+            bus: soundlist.bus_name(si.bus).to_owned(),
+            notes: si.notes().to_owned(),
+            osc: si.osc.iter().map(|m| m.to_string()).collect(),
+            markers: si
+                .markers
+                .iter()
+                .map(|m| MarkerShow {
+                    name: m.name.clone(),
+                    position: m.position.as_secs_f32(),
+                })
+                .collect(),
+            layers: si
+                .layers
+                .iter()
+                .map(|l| LayerShow {
+                    file: l.file.clone(),
+                    level: l.level,
+                    offset: l.offset.as_secs_f32(),
+                })
+                .collect(),
+        }
+    }
+
+    fn apply(&self, si: &mut SoundItem, bus: usize, warnings: &mut Vec<String>) {
+        si.local_volume = self.local_volume.clamp(-2.0, 2.0);
+        if self.display_name.is_some() {
+            si.display_name = self.display_name.clone();
+        }
+        si.bus = bus;
+        for (input, value) in [(0, self.fade_in), (1, self.fade_out), (2, self.trim_in)] {
+            si.fade_tab_content[input].input = if value > 0.0 {
+                value.to_string()
+            } else {
+                String::new()
+            };
+        }
+        si.fade_tab_content[3].input = self.trim_out.map(|t| t.to_string()).unwrap_or_default();
+        si.apply_trim_inputs();
+        si.set_notes(self.notes.clone());
+        let mut osc = Vec::new();
+        for text in &self.osc {
+            match CueMessage::parse(text) {
+                Ok(message) => osc.push(message),
+                Err(e) => warnings.push(format!("OSC of {} : {}", self.name, e)),
+            }
+        }
+        si.set_osc(osc);
+        for marker in &self.markers {
+            let marker = Marker {
+                name: marker.name.clone(),
+                position: seconds(marker.position),
+            };
+            if let Err(e) = si.add_marker(marker) {
+                warnings.push(e);
+            }
+        }
+    }
+}
+
+fn seconds(value: f32) -> Duration {
+    if value.is_finite() && value > 0.0 {
+        Duration::from_secs_f32(value)
+    } else {
+        Duration::ZERO
+    }
+}
+
+/// Path of `target` from `base`, going up with `..` when needed
+fn relative_path(target: &Path, base: &Path) -> String {
+    if target.as_os_str().is_empty() {
+        return String::new();
+    }
+    let absolute = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let (target, base) = (absolute(target), absolute(base));
+    let target_parts: Vec<Component> = target.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+    let common = target_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        // Another drive, keep it absolute
+        return target.to_string_lossy().into_owned();
+    }
+    let mut relative = PathBuf::new();
+    for _ in common..base_parts.len() {
+        relative.push("..");
+    }
+    for part in &target_parts[common..] {
+        relative.push(part);
+    }
+    if relative.as_os_str().is_empty() {
+        ".".to_owned()
+    } else {
+        relative.to_string_lossy().into_owned()
+    }
+}
+
+/// Write the show file, the sound directory is stored relative to it
+pub fn save(tab_manager: &TabManager, path: &Path) -> Result<(), String> {
+    let show_dir = path.parent().unwrap_or(Path::new("."));
+    let show = Show::capture(tab_manager, show_dir);
+    let text = toml::to_string_pretty(&show)
+        .map_err(|e| format!("Cannot write show file {} : {}", path.to_string_lossy(), e))?;
+    fs::write(path, text)
+        .map_err(|e| format!("Cannot write show file {} : {}", path.to_string_lossy(), e))
+}
+
+/// Read a show file and replace the state of every tab, returns the problems that did not stop the loading
+pub fn load(tab_manager: &mut TabManager, path: &Path) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read show file {} : {}", path.to_string_lossy(), e))?;
+    let show: Show = toml::from_str(&text)
+        .map_err(|e| format!("Invalid show file {} : {}", path.to_string_lossy(), e))?;
+    let show_dir = path.parent().unwrap_or(Path::new("."));
+    Ok(show.apply(tab_manager, show_dir))
+}
+
+/// Session state written by the autosave, removed on a clean exit
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Recovery {
+    /// Show file open when the state was saved
+    pub show_file: Option<PathBuf>,
+    pub show: Show,
+}
+
+/// Keeps the recovery file up to date, its presence at start means the last exit was not clean
+#[derive(Debug)]
+pub struct Autosave {
+    pub path: PathBuf,
+    last_save: Instant,
+    /// Something changed since the last write
+    pub edited: bool,
+    /// A recovery file was found at start and the operator has not answered yet
+    pub offer_restore: bool,
+}
+
+impl Autosave {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            offer_restore: path.is_file(),
+            path,
+            last_save: Instant::now(),
+            edited: false,
+        }
+    }
+
+    /// The recovery file must be rewritten now
+    pub fn is_due(&self) -> bool {
+        !self.offer_restore
+            && ((self.edited && self.last_save.elapsed() >= AUTOSAVE_MIN_GAP)
+                || self.last_save.elapsed() >= AUTOSAVE_INTERVAL)
+    }
+
+    pub fn saved(&mut self) {
+        self.last_save = Instant::now();
+        self.edited = false;
+    }
+}
+
+/// Where the autosave writes, in the home directory so it survives a reboot
+pub fn recovery_path() -> PathBuf {
+    home_dir().join(".utilscord_recovery.toml")
+}
+
+/// Home directory of the operator, the temporary directory when there is none
+pub fn home_dir() -> PathBuf {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+}
+
+/// Write the recovery file through a temporary file, a crash while writing keeps the previous one
+pub fn save_recovery(tab_manager: &TabManager, path: &Path) -> Result<(), String> {
+    let show_dir = path.parent().unwrap_or(Path::new("."));
+    let recovery = Recovery {
+        show_file: tab_manager.show_path.clone(),
+        show: Show::capture(tab_manager, show_dir),
+    };
+    let error = |e: String| {
+        format!(
+            "Cannot write recovery file {} : {}",
+            path.to_string_lossy(),
+            e
+        )
+    };
+    let text = toml::to_string_pretty(&recovery).map_err(|e| error(e.to_string()))?;
+    let temporary = path.with_extension("toml.tmp");
+    fs::write(&temporary, text).map_err(|e| error(e.to_string()))?;
+    fs::rename(&temporary, path).map_err(|e| error(e.to_string()))
+}
+
+/// Restore the state of every tab from the recovery file
+pub fn load_recovery(tab_manager: &mut TabManager, path: &Path) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Cannot read recovery file {} : {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    let recovery: Recovery = toml::from_str(&text)
+        .map_err(|e| format!("Invalid recovery file {} : {}", path.to_string_lossy(), e))?;
+    let show_dir = path.parent().unwrap_or(Path::new("."));
+    tab_manager.show_path = recovery.show_file;
+    Ok(recovery.show.apply(tab_manager, show_dir))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn relative_sound_directory() {
+        assert_eq!(
+            relative_path(Path::new("/show/sounds"), Path::new("/show")),
+            "sounds"
+        );
+        assert_eq!(
+            relative_path(Path::new("/media/sounds"), Path::new("/show/act1")),
+            "../../media/sounds"
+        );
+        assert_eq!(relative_path(Path::new("/show"), Path::new("/show")), ".");
+    }
+
+    #[test]
+    fn show_round_trip() {
+        let show = Show {
+            sounds: SoundsShow {
+                directory: "sounds".to_owned(),
+                standby: Some("intro.wav".to_owned()),
+                items: vec![ItemShow {
+                    name: "intro.wav".to_owned(),
+                    local_volume: -0.25,
+                    fade_in: 2.0,
+                    trim_out: Some(12.5),
+                    bus: "Music".to_owned(),
+                    osc: vec!["video /clip/3/start i:1 s:\"Intro\"".to_owned()],
+                    markers: vec![MarkerShow {
+                        name: "Chorus".to_owned(),
+                        position: 4.5,
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            dmx: DmxShow {
+                address: 17,
+                faders: vec![255, 0, 10, 20],
+                ..Default::default()
+            },
+            osc: OscShow {
+                listen: "127.0.0.1:8000".to_owned(),
+                namespace: "Stage".to_owned(),
+                mappings: vec![
+                    Mapping::parse("/desk/fader/12 -> DMXChan/12 0..1 => 0..255").unwrap(),
+                ],
+                reply: "127.0.0.1:9000".to_owned(),
+                destinations: vec![Destination {
+                    name: "video".to_owned(),
+                    address: "192.168.1.50:7000".to_owned(),
+                }],
+            },
+        };
+        let text = toml::to_string_pretty(&show).unwrap();
+        assert_eq!(toml::from_str::<Show>(&text).unwrap(), show);
+        let recovery = Recovery {
+            show_file: Some(PathBuf::from("/show/show.toml")),
+            show,
+        };
+        let text = toml::to_string_pretty(&recovery).unwrap();
+        assert_eq!(toml::from_str::<Recovery>(&text).unwrap(), recovery);
+        // Missing sections and fields fall back to the defaults
+        let partial: Show = toml::from_str("[dmx]\naddress = 5\n").unwrap();
+        assert_eq!(partial.dmx.address, 5);
+        assert_eq!(partial.sounds, SoundsShow::default());
+    }
+
+    #[test]
+    fn item_fades_keep_their_decimals() {
+        let soundlist = SoundList::from_dir(String::new());
+        let item = ItemShow {
+            name: "intro.wav".to_owned(),
+            fade_in: 2.5,
+            fade_out: 0.75,
+            ..Default::default()
+        };
+        let mut si = SoundItem::new(item.name.clone(), Duration::from_secs(10));
+        item.apply(&mut si, 0, &mut Vec::new());
+        let saved = ItemShow::capture(&si, &soundlist);
+        assert_eq!((saved.fade_in, saved.fade_out), (2.5, 0.75));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::interact_mod::component::{audio, ListenAddress};
+use crate::interact_mod::osc_address;
+use clap::{error::ErrorKind, CommandFactory, Parser, ValueEnum};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+
+/// How the DMX faders reach the lights
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DmxBackend {
+    /// Open DMX USB interface on a serial port
+    #[default]
+    OpenDmx,
+    /// No DMX output, the DMX tab is left idle
+    None,
+}
+
+/// Sound bank, OSC and DMX for live events and theatre
+#[derive(Parser, Debug, Default)]
+#[command(version, about)]
+pub struct Cli {
+    /// Sound directory, cue list (.csv) or show file (.toml)
+    #[arg(value_name = "PATH", value_parser = existing_path, conflicts_with_all = ["sounds", "show"])]
+    pub path: Option<PathBuf>,
+    /// Sound directory or cue list (.csv) to load in the Sound Bank
+    #[arg(long, value_name = "DIR|CSV", value_parser = sounds_path)]
+    pub sounds: Option<PathBuf>,
+    /// Show file (.toml) to open, its sound directory comes from the show
+    #[arg(long, value_name = "FILE", value_parser = show_path)]
+    pub show: Option<PathBuf>,
+    /// Addresses the OSC listener binds to, repeat it or separate them by commas. IPv6 goes in
+    /// brackets like [::]:8000, a multicast address joins the group on the default interface,
+    /// tcp://IP:PORT listens over TCP
+    #[arg(long, value_name = "IP:PORT", value_delimiter = ',', value_parser = ListenAddress::parse)]
+    pub osc_listen: Vec<ListenAddress>,
+    /// First part of every OSC address, /Utilscord/Play/0 by default
+    #[arg(long, value_name = "NAME", value_parser = osc_namespace)]
+    pub osc_namespace: Option<String>,
+    /// Where OSC replies are sent
+    #[arg(long, value_name = "HOST:PORT", value_parser = host_port)]
+    pub osc_reply: Option<SocketAddr>,
+    /// Serial port of the DMX interface, /dev/ttyUSB0 on Linux and COM3 on Windows otherwise
+    #[arg(long, value_name = "PORT")]
+    pub dmx_port: Option<String>,
+    /// DMX output
+    #[arg(long, value_enum, default_value_t)]
+    pub dmx_backend: DmxBackend,
+    /// Audio output device, the default output otherwise
+    #[arg(long, value_name = "NAME", value_parser = audio_device)]
+    pub audio_device: Option<String>,
+    /// Run without the terminal interface, driven by OSC only. Needs a show file
+    #[arg(long)]
+    pub headless: bool,
+    /// File the headless mode logs to, stdout otherwise
+    #[arg(long, value_name = "FILE", requires = "headless")]
+    pub log: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Parse the command line, the positional path goes to --show or --sounds from its extension.
+    /// Exits with the usage on an invalid value
+    pub fn parse_args() -> Self {
+        let mut cli = Cli::parse();
+        if let Some(path) = cli.path.take() {
+            if path.extension().is_some_and(|e| e == "toml") {
+                cli.show = Some(path);
+            } else {
+                cli.sounds = Some(path);
+            }
+        }
+        if cli.headless && cli.show.is_none() {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "--headless needs a show file, give --show or a .toml PATH",
+                )
+                .exit();
+        }
+        if let Some(name) = &cli.audio_device {
+            audio::set_output_device(name);
+        }
+        cli
+    }
+}
+
+fn existing_path(text: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(text);
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(format!("{} does not exist", text))
+    }
+}
+
+fn sounds_path(text: &str) -> Result<PathBuf, String> {
+    let path = existing_path(text)?;
+    if path.is_dir() || path.extension().is_some_and(|e| e == "csv") {
+        Ok(path)
+    } else {
+        Err(format!(
+            "{} is neither a directory nor a .csv cue list",
+            text
+        ))
+    }
+}
+
+fn show_path(text: &str) -> Result<PathBuf, String> {
+    let path = existing_path(text)?;
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(format!("{} is not a show file", text))
+    }
+}
+
+/// `host:port` with a host name or an IP, resolved once at startup
+fn host_port(text: &str) -> Result<SocketAddr, String> {
+    text.to_socket_addrs()
+        .map_err(|e| format!("{} is not a valid HOST:PORT : {}", text, e))?
+        .next()
+        .ok_or_else(|| format!("{} does not resolve to an address", text))
+}
+
+fn osc_namespace(text: &str) -> Result<String, String> {
+    osc_address::check_namespace(text)?;
+    Ok(text.to_owned())
+}
+
+fn audio_device(text: &str) -> Result<String, String> {
+    let devices = audio::output_devices();
+    if devices.iter().any(|d| d == text) {
+        Ok(text.to_owned())
+    } else if devices.is_empty() {
+        Err(format!(
+            "no audio output named {}, no output device found",
+            text
+        ))
+    } else {
+        Err(format!(
+            "no audio output named {}, available : {}",
+            text,
+            devices.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn command_line_values_are_checked() {
+        let cli = Cli::try_parse_from([
+            "utilscord",
+            "--osc-listen",
+            "0.0.0.0:8000,[::1]:8000",
+            "--osc-listen",
+            "239.1.2.3:9000",
+            "--osc-listen",
+            "tcp://0.0.0.0:8001",
+            "--osc-namespace",
+            "Stage",
+            "--osc-reply",
+            "127.0.0.1:9000",
+            "--dmx-backend",
+            "none",
+            "--headless",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.osc_listen,
+            [
+                ListenAddress::Udp("0.0.0.0:8000".parse().unwrap()),
+                ListenAddress::Udp("[::1]:8000".parse().unwrap()),
+                ListenAddress::Udp("239.1.2.3:9000".parse().unwrap()),
+                ListenAddress::Tcp("0.0.0.0:8001".parse().unwrap()),
+            ]
+        );
+        assert_eq!(cli.osc_namespace.as_deref(), Some("Stage"));
+        assert_eq!(cli.osc_reply, Some("127.0.0.1:9000".parse().unwrap()));
+        assert_eq!(cli.dmx_backend, DmxBackend::None);
+        assert!(cli.headless);
+
+        for args in [
+            ["utilscord", "--osc-listen", "0.0.0.0"],
+            ["utilscord", "--osc-namespace", "Stage/Left"],
+            ["utilscord", "--osc-listen", "tcp://239.1.2.3:9000"],
+            ["utilscord", "--osc-reply", "nowhere"],
+            ["utilscord", "--dmx-backend", "artnet"],
+            ["utilscord", "--show", "/no/such/show.toml"],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err(), "{:?}", args);
+        }
+    }
+}
@@ -5,12 +5,28 @@ use std::env;
 #[derive(Debug)]
 pub struct DMXHandler {
     pub dmx_connection_option: Option<DMXSerial>,
+    /// Channel and level sent on panic, every other channel goes to 0. Empty is a blackout
+    pub safe_look: Vec<(usize, u8)>,
+    /// False with `--dmx-backend none`, nothing is opened nor sent
+    pub enabled: bool,
 }
 
 impl DMXHandler {
     fn empty() -> Self {
         Self {
             dmx_connection_option: None,
+            safe_look: Vec::new(),
+            enabled: true,
+        }
+    }
+    /// A handler without DMX output, the DMX tab shows why
+    pub fn disabled(ctx: &mut Content) -> Self {
+        if let Content::Dmx(.., dmx_status) = ctx {
+            *dmx_status = "DMX disabled (--dmx-backend none)".into();
+        }
+        Self {
+            enabled: false,
+            ..Self::empty()
         }
     }
     /// Open a dmx connection. Returns a DMXHandler Holding the connection.
@@ -36,6 +52,7 @@ impl DMXHandler {
                     *dmx_status = "Running".into();
                     return Ok(Self {
                         dmx_connection_option: Some(dmx_chan),
+                        ..Self::empty()
                     });
                 }
                 Err(e) => {
@@ -58,6 +75,9 @@ impl DMXHandler {
     }
     /// Main function to handle the dmx connection. Called every frame to update dmx status.
     pub fn handle_dmx(&mut self, ctx: &mut Content) -> Result<(), ()> {
+        if !self.enabled {
+            return Err(());
+        }
         if let Some(dmx_connection) = &mut self.dmx_connection_option {
             if dmx_connection.check_agent().is_ok() {
                 // DMX Connection exists
@@ -145,6 +165,49 @@ impl DMXHandler {
 
         Ok(())
     }
+    /// Level of the channel in the safe look
+    fn safe_level(&self, channel: usize) -> u8 {
+        self.safe_look
+            .iter()
+            .find(|(c, _)| *c == channel)
+            .map(|(_, level)| *level)
+            .unwrap_or(0)
+    }
+    /// Send the safe look on every channel and show it on the faders
+    pub fn panic(&mut self, ctx: &mut Content) -> Result<(), String> {
+        if let Content::Dmx(f1, f2, f3, f4, adr, _serial, dmx_status) = ctx {
+            for (id, fader) in [f1, f2, f3, f4].into_iter().enumerate() {
+                fader.value = self.safe_level(adr.wrapping_add(id).clamp(1, DMX_CHANNELS));
+            }
+            let levels: Vec<u8> = (1..=DMX_CHANNELS).map(|c| self.safe_level(c)).collect();
+            let Some(dmx_connection) = &mut self.dmx_connection_option else {
+                return Err(String::from("No DMX connection found !"));
+            };
+            for (channel, level) in (1..=DMX_CHANNELS).zip(levels) {
+                dmx_connection
+                    .set_channel(channel, level)
+                    .map_err(|e| format!("Cannot set {} to {} : {}", channel, level, e))?;
+            }
+            *dmx_status = if self.safe_look.is_empty() {
+                "Panic : Blackout".into()
+            } else {
+                "Panic : Safe look".into()
+            };
+        }
+        Ok(())
+    }
+    /// Keep the current levels of the faders as the safe look, returns the number of channels stored
+    pub fn store_safe_look(&mut self, ctx: &Content) -> usize {
+        if let Content::Dmx(f1, f2, f3, f4, adr, ..) = ctx {
+            self.safe_look = [f1, f2, f3, f4]
+                .into_iter()
+                .enumerate()
+                .map(|(id, fader)| (adr.wrapping_add(id).clamp(1, DMX_CHANNELS), fader.value))
+                .filter(|(_, level)| *level > 0)
+                .collect();
+        }
+        self.safe_look.len()
+    }
     /// Use this function after you changed the DMX Content, like fader value, dmx adress.
     pub fn update_dmx(&mut self, ctx: &mut Content) {
         if let Content::Dmx(f1, f2, f3, f4, adr, _serial, dmx_status) = ctx {
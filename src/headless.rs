@@ -0,0 +1,126 @@
+use crate::cli::Cli;
+use crate::interact_mod::{component::Content, TabManager};
+use signal_hook::consts::TERM_SIGNALS;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest wait for an OSC packet, the stop signal is checked in between
+const TICK: Duration = Duration::from_millis(50);
+
+/// Lines of the headless mode with the UTC time, on stdout or appended to a file
+struct Log {
+    file: Option<File>,
+}
+
+impl Log {
+    fn open(path: Option<&Path>) -> Result<Self, String> {
+        let file = match path {
+            Some(path) => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| format!("Cannot open log {} : {}", path.to_string_lossy(), e))?,
+            ),
+            None => None,
+        };
+        Ok(Self { file })
+    }
+
+    fn line(&mut self, text: &str) {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            % 86400;
+        let line = format!(
+            "{:02}:{:02}:{:02} {}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            text
+        );
+        match &mut self.file {
+            Some(file) => {
+                let _ = writeln!(file, "{}", line);
+            }
+            None => println!("{}", line),
+        }
+    }
+}
+
+fn dmx_status(tab_manager: &TabManager) -> &str {
+    match &tab_manager.tabs[2].content {
+        Content::Dmx(.., dmx_status) => dmx_status,
+        _ => "",
+    }
+}
+
+/// Play the show with OSC as the only control until SIGTERM or SIGINT, then fade out and exit
+pub fn run(cli: &Cli) -> Result<(), String> {
+    let mut log = Log::open(cli.log.as_deref())?;
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in TERM_SIGNALS {
+        signal_hook::flag::register(*signal, Arc::clone(&stop))
+            .map_err(|e| format!("Cannot handle signal {} : {}", signal, e))?;
+    }
+
+    let mut tab_manager = TabManager::new(cli);
+    if tab_manager.show_path.is_none() {
+        return Err(tab_manager.status().to_owned());
+    }
+    log.line(tab_manager.status());
+    if tab_manager.autosave.offer_restore {
+        match tab_manager.set_aside_recovery() {
+            Ok(aside) => log.line(&format!(
+                "Recovery file of an unclean exit moved to {}, the show file is used",
+                aside.to_string_lossy()
+            )),
+            // Still offered, the autosave does not overwrite it
+            Err(e) => log.line(&format!(
+                "{}, it is kept and this session is not autosaved",
+                e
+            )),
+        }
+    }
+    match (&tab_manager.osc_receiver, &tab_manager.tabs[1].content) {
+        (Some(_), Content::Osc(ipinput)) => {
+            log.line(&format!("Listening to OSC on {}", ipinput.input))
+        }
+        _ => log.line("Not listening to OSC, set osc.listen in the show or --osc-listen"),
+    }
+    log.line(&format!("DMX : {}", dmx_status(&tab_manager)));
+
+    let mut status = tab_manager.status().to_owned();
+    let mut dmx = dmx_status(&tab_manager).to_owned();
+    while !stop.load(Ordering::Relaxed) {
+        if tab_manager.osc_receiver.is_none() {
+            thread::sleep(TICK);
+        }
+        for line in tab_manager.handle_osc(TICK) {
+            log.line(&line);
+        }
+        tab_manager.send_feedback();
+        tab_manager.autosave();
+        let _ = tab_manager
+            .dmx_handler
+            .handle_dmx(&mut tab_manager.tabs[2].content);
+        if tab_manager.status() != status {
+            status = tab_manager.status().to_owned();
+            log.line(&status);
+        }
+        if dmx_status(&tab_manager) != dmx {
+            dmx = dmx_status(&tab_manager).to_owned();
+            log.line(&format!("DMX : {}", dmx));
+        }
+    }
+    log.line("Stopping, fading out");
+    tab_manager.shutdown();
+    log.line("Stopped");
+    Ok(())
+}
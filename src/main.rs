@@ -6,7 +6,9 @@ use std::{path::Path, rc::Rc};
 #[path = "applib/interact.rs"]
 mod interact_mod;
 use interact_mod::{component::Content, TabManager};
+mod cli;
 mod dmx;
+mod headless;
 
 struct Utilscord {
     should_quit: bool,
@@ -14,17 +16,15 @@ struct Utilscord {
     tab_manager: TabManager,
 }
 
-impl Default for Utilscord {
-    fn default() -> Self {
+impl Utilscord {
+    fn new(cli: &cli::Cli) -> Self {
         Self {
             should_quit: false,
             quit_key_code: Rc::new([KeyCode::Char('q'), KeyCode::Char('Q'), KeyCode::Esc]),
-            tab_manager: TabManager::default(),
+            tab_manager: TabManager::new(cli),
         }
     }
-}
 
-impl Utilscord {
     pub fn run(&mut self) {
         let mut terminal = ratatui::init();
         let mut file_explorer = FileExplorer::new().unwrap();
@@ -38,6 +38,7 @@ impl Utilscord {
             .unwrap();
         loop {
             if self.should_quit {
+                self.tab_manager.end_session();
                 break;
             }
             if terminal
@@ -47,7 +48,9 @@ impl Utilscord {
                 break;
             }
             self.handle_events(&mut file_explorer);
-            self.handle_osc();
+            self.tab_manager.handle_osc(Duration::from_millis(50));
+            self.tab_manager.send_feedback();
+            self.tab_manager.autosave();
             let _ = self
                 .tab_manager
                 .dmx_handler
@@ -57,31 +60,31 @@ impl Utilscord {
         ratatui::restore();
     }
 
-    fn handle_osc(&mut self) {
-        if let Some(rcvr) = &mut self.tab_manager.osc_receiver {
-            match rcvr.recv_timeout(Duration::from_millis(50)) {
-                Ok(packet) => match packet {
-                    rosc::OscPacket::Message(osc_message) => {
-                        if let Ok(()) = self.tab_manager.osc_message_interaction(osc_message) {};
-                    }
-                    rosc::OscPacket::Bundle(osc_bundle) => {
-                        self.tab_manager.osc_bundle_interaction(osc_bundle);
-                    }
-                },
-                Err(_e) => {}
-            }
-        }
-    }
-
     fn handle_events(&mut self, file_explorer: &mut ratatui_explorer::FileExplorer) {
         let timeout = Duration::from_millis(50);
 
         if !event::poll(timeout).unwrap() {
             return;
         }
-        if let Ok(event) = event::read() {
-            if let Event::Key(key) = event {
+        if let Ok(mut event) = event::read() {
+            if let Event::Key(pressed) = event {
+                // KEYMAP : the tabs read the default keys of the actions
+                let Some(key) = self.tab_manager.translate_key(pressed) else {
+                    return;
+                };
+                event = Event::Key(key);
                 if key.kind == KeyEventKind::Press {
+                    // HELP EVENT
+                    if self.tab_manager.show_help {
+                        if matches!(key.code, KeyCode::F(1) | KeyCode::Esc) {
+                            self.tab_manager.show_help = false;
+                        }
+                        return;
+                    }
+                    if key.code == KeyCode::F(1) {
+                        self.tab_manager.show_help = true;
+                        return;
+                    }
                     // QUIT EVENT
                     let keycodes = Rc::clone(&self.quit_key_code);
                     for keycode in keycodes.iter() {
@@ -89,7 +92,56 @@ impl Utilscord {
                             self.should_quit = true;
                         }
                     }
+                    // PANIC EVENT
+                    if key.modifiers == KeyModifiers::CONTROL {
+                        match key.code {
+                            KeyCode::Char('x') => {
+                                let _ = self.tab_manager.panic();
+                                return;
+                            }
+                            KeyCode::Char('r') => {
+                                self.tab_manager.clear_panic();
+                                return;
+                            }
+                            _ => (),
+                        }
+                    }
+                    // SHOW FILE AND HISTORY EVENT
+                    if key.modifiers == KeyModifiers::CONTROL {
+                        match key.code {
+                            KeyCode::Char('s') => {
+                                self.tab_manager.save_show_with_status();
+                                return;
+                            }
+                            KeyCode::Char('o') => {
+                                self.tab_manager.browsing_show = true;
+                                return;
+                            }
+                            KeyCode::Char('e') => {
+                                self.tab_manager.export_cue_sheet();
+                                return;
+                            }
+                            KeyCode::Char('z') => {
+                                self.tab_manager.undo();
+                                return;
+                            }
+                            KeyCode::Char('y') => {
+                                self.tab_manager.redo();
+                                return;
+                            }
+                            _ => (),
+                        }
+                    }
+                    // PAUSE ALL EVENT
+                    if (key.code, key.modifiers) == (KeyCode::Char('p'), KeyModifiers::CONTROL) {
+                        self.tab_manager.toggle_pause_all();
+                        return;
+                    }
                     // MOVE EVENT
+                    if self.tab_manager.browsing_show || self.tab_manager.autosave.offer_restore {
+                        self.tab_manager.handle_event(event, file_explorer);
+                        return;
+                    }
                     match &self.tab_manager.get_selected_tab_mut().content {
                         Content::MainMenu(..) => {
                             if key.modifiers == KeyModifiers::SHIFT {
@@ -164,6 +216,17 @@ impl Utilscord {
                             }
                             _ => (),
                         },
+                        Content::Monitor(..) => {
+                            if key.modifiers == KeyModifiers::SHIFT
+                                && !self.tab_manager.tabs[3].is_used()
+                            {
+                                match key.code {
+                                    KeyCode::Left => self.tab_manager.previous(),
+                                    KeyCode::Right => self.tab_manager.next(),
+                                    _ => (),
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -173,5 +236,13 @@ impl Utilscord {
 }
 
 fn main() {
-    Utilscord::default().run();
+    let cli = cli::Cli::parse_args();
+    if cli.headless {
+        if let Err(e) = headless::run(&cli) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    Utilscord::new(&cli).run();
 }